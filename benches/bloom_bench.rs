@@ -1,8 +1,22 @@
 //! Criterion benchmarks for Bloom filter performance.
 
 use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use kaka::blocked::BlockedBloomFilter;
 use kaka::bloom::BloomFilter;
 
+/// Filter size for the standard-vs-blocked comparison; large enough
+/// (~12 MB) that the bit array does not fit in L2 cache.
+const COMPARISON_CAPACITY: usize = 10_000_000;
+
+/// Number of URLs inserted or probed per comparison iteration.
+const COMPARISON_BATCH: usize = 100_000;
+
+fn comparison_urls(offset: usize) -> Vec<String> {
+    (offset..offset + COMPARISON_BATCH)
+        .map(|i| format!("https://example.com/page{}", i))
+        .collect()
+}
+
 /// Benchmark Bloom filter insertion throughput.
 fn bloom_insert_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("bloom_insert");
@@ -33,5 +47,125 @@ fn bloom_contains_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bloom_insert_benchmark, bloom_contains_benchmark);
+/// Compare insert throughput of the standard and blocked filters.
+fn blocked_insert_benchmark(c: &mut Criterion) {
+    let urls = comparison_urls(0);
+    let mut standard = BloomFilter::new(COMPARISON_CAPACITY, 0.01);
+    let mut blocked = BlockedBloomFilter::new(COMPARISON_CAPACITY, 0.01);
+
+    let mut group = c.benchmark_group("bloom_vs_blocked_insert");
+    group.throughput(Throughput::Elements(COMPARISON_BATCH as u64));
+
+    group.bench_function("standard", |b| {
+        b.iter(|| {
+            for url in &urls {
+                standard.insert(black_box(url));
+            }
+        });
+    });
+
+    group.bench_function("blocked", |b| {
+        b.iter(|| {
+            for url in &urls {
+                blocked.insert(black_box(url));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+/// Compare lookup throughput (all misses) and report measured FP rates.
+fn blocked_contains_benchmark(c: &mut Criterion) {
+    let mut standard = BloomFilter::new(COMPARISON_CAPACITY, 0.01);
+    let mut blocked = BlockedBloomFilter::new(COMPARISON_CAPACITY, 0.01);
+    for i in 0..COMPARISON_CAPACITY {
+        let url = format!("https://example.com/page{}", i);
+        standard.insert(&url);
+        blocked.insert(&url);
+    }
+
+    let probes = comparison_urls(COMPARISON_CAPACITY);
+    let fp = |hits: usize| hits as f64 / probes.len() as f64;
+    println!(
+        "measured FP rate at capacity: standard {:.4}, blocked {:.4} ({} vs {} bits)",
+        fp(probes.iter().filter(|u| standard.contains(u)).count()),
+        fp(probes.iter().filter(|u| blocked.contains(u)).count()),
+        standard.num_bits(),
+        blocked.num_bits(),
+    );
+
+    let mut group = c.benchmark_group("bloom_vs_blocked_contains");
+    group.throughput(Throughput::Elements(COMPARISON_BATCH as u64));
+
+    group.bench_function("standard", |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter(|u| standard.contains(black_box(u)))
+                .count()
+        });
+    });
+
+    group.bench_function("blocked", |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter(|u| blocked.contains(black_box(u)))
+                .count()
+        });
+    });
+
+    group.finish();
+}
+
+/// Compare one-at-a-time and prefetching batch operations on a filter
+/// that does not fit in cache.
+fn batch_benchmark(c: &mut Criterion) {
+    let urls = comparison_urls(0);
+    let refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+    let mut bloom = BloomFilter::new(COMPARISON_CAPACITY, 0.01);
+
+    let mut group = c.benchmark_group("bloom_batch");
+    group.throughput(Throughput::Elements(COMPARISON_BATCH as u64));
+
+    group.bench_function("insert_single", |b| {
+        b.iter(|| {
+            for url in &refs {
+                bloom.insert(black_box(url));
+            }
+        });
+    });
+
+    group.bench_function("insert_batch", |b| {
+        b.iter(|| bloom.insert_batch(black_box(&refs)));
+    });
+
+    let probes = comparison_urls(COMPARISON_CAPACITY);
+    let probes: Vec<&str> = probes.iter().map(String::as_str).collect();
+
+    group.bench_function("contains_single", |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter(|u| bloom.contains(black_box(u)))
+                .count()
+        });
+    });
+
+    group.bench_function("contains_batch", |b| {
+        b.iter(|| bloom.contains_batch(black_box(&probes)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bloom_insert_benchmark,
+    bloom_contains_benchmark,
+    blocked_insert_benchmark,
+    blocked_contains_benchmark,
+    batch_benchmark
+);
 criterion_main!(benches);
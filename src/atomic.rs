@@ -0,0 +1,200 @@
+//! Lock-free Bloom filter shared across threads.
+//!
+//! [`BloomFilter::insert`] takes `&mut self`, so sharing one filter
+//! between crawler threads needs a lock around every call. This variant
+//! stores its bit array as [`AtomicU64`] words and sets bits with
+//! `fetch_or`, so `insert` and `contains` both take `&self` and the
+//! filter can live behind a plain `Arc`.
+//!
+//! It uses the same sizing and probe sequence as [`BloomFilter`], so a
+//! filter can be converted in either direction without rehashing.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::bloom::{BloomFilter, hash_pair, hashable_pair, optimal_params, probe_index};
+use crate::hash::{DEFAULT_SEED, StableHash};
+
+/// Bloom filter whose operations take `&self`.
+///
+/// # Characteristics
+/// - No false negatives
+/// - `Send + Sync`; no locks on the hot path
+/// - Bit-for-bit compatible with [`BloomFilter`]
+///
+/// # Fields
+/// - `words`: Bit array packed into atomic 64-bit words
+/// - `num_bits`: Size of the bit array (m)
+/// - `num_hashes`: Number of hash functions (k)
+/// - `seed`: Seed of the stable hash functions
+/// - `items_inserted`: Count of inserted elements (n)
+pub struct AtomicBloomFilter {
+    words: Vec<AtomicU64>,
+    num_bits: usize,
+    num_hashes: u32,
+    seed: u64,
+    items_inserted: AtomicU64,
+}
+
+impl AtomicBloomFilter {
+    /// Create a new atomic Bloom filter using [`DEFAULT_SEED`].
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of elements (n)
+    /// - `fp_rate`: Desired false positive probability (p)
+    pub fn new(capacity: usize, fp_rate: f64) -> Self {
+        Self::with_seed(capacity, fp_rate, DEFAULT_SEED)
+    }
+
+    /// Create a new atomic Bloom filter with explicit hash seed.
+    pub fn with_seed(capacity: usize, fp_rate: f64, seed: u64) -> Self {
+        let (m, k) = optimal_params(capacity, fp_rate);
+
+        Self {
+            words: (0..m.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            num_bits: m,
+            num_hashes: k,
+            seed,
+            items_inserted: AtomicU64::new(0),
+        }
+    }
+
+    /// Insert an element into the filter.
+    ///
+    /// Returns `true` if every one of the element's bits was already
+    /// set, i.e. the element was **possibly present** before this call,
+    /// and `false` if this call set at least one new bit.
+    ///
+    /// When several threads insert the *same* new element concurrently,
+    /// more than one of them may observe `false`; each bit is only set
+    /// once, but the `k` bits are not claimed as a unit. Callers that
+    /// need exactly one winner per key must serialize on the key.
+    pub fn insert(&self, value: &str) -> bool {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.seed);
+        self.insert_prehashed(h1, h2)
+    }
+
+    /// Check whether an element is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.seed);
+        self.contains_prehashed(h1, h2)
+    }
+
+    /// Insert any hashable value, as
+    /// [`BloomFilter::insert_hashable`].
+    ///
+    /// Returns whether every bit was already set, as [`insert`](Self::insert).
+    pub fn insert_hashable<T: StableHash + ?Sized>(&self, value: &T) -> bool {
+        let (h1, h2) = hashable_pair(value, self.seed);
+        self.insert_prehashed(h1, h2)
+    }
+
+    /// Check whether a hashable value is possibly in the set.
+    pub fn contains_hashable<T: StableHash + ?Sized>(&self, value: &T) -> bool {
+        let (h1, h2) = hashable_pair(value, self.seed);
+        self.contains_prehashed(h1, h2)
+    }
+
+    /// Insert using caller-supplied base hashes for double hashing.
+    ///
+    /// Returns whether every bit was already set, as [`insert`](Self::insert).
+    pub fn insert_prehashed(&self, h1: u64, h2: u64) -> bool {
+        let m = self.num_bits as u64;
+
+        let mut present = true;
+        for i in 0..self.num_hashes {
+            let (word, mask) = Self::locate(probe_index(h1, h2, i, m));
+            let previous = self.words[word].fetch_or(mask, Ordering::Relaxed);
+            present &= previous & mask != 0;
+        }
+
+        self.items_inserted.fetch_add(1, Ordering::Relaxed);
+        present
+    }
+
+    /// Check membership using caller-supplied base hashes.
+    pub fn contains_prehashed(&self, h1: u64, h2: u64) -> bool {
+        let m = self.num_bits as u64;
+
+        (0..self.num_hashes).all(|i| {
+            let (word, mask) = Self::locate(probe_index(h1, h2, i, m));
+            self.words[word].load(Ordering::Relaxed) & mask != 0
+        })
+    }
+
+    /// Estimate the current false positive rate.
+    ///
+    /// Formula:
+    /// `(1 - e^(-k * n / m))^k`
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        let n = self.items_inserted() as f64;
+        let m = self.num_bits as f64;
+
+        (1.0 - (-k * n / m).exp()).powf(k)
+    }
+
+    /// Copy the current bits into a regular [`BloomFilter`].
+    ///
+    /// Inserts racing with the copy may or may not be included.
+    pub fn to_bloom_filter(&self) -> BloomFilter {
+        let words = self
+            .words
+            .iter()
+            .map(|w| w.load(Ordering::Relaxed))
+            .collect();
+
+        BloomFilter::from_words(
+            words,
+            self.num_bits,
+            self.num_hashes,
+            self.seed,
+            self.items_inserted(),
+        )
+    }
+
+    /// Size of the bit array (m).
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Number of hash functions (k).
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Number of insert calls made so far (n).
+    pub fn items_inserted(&self) -> u64 {
+        self.items_inserted.load(Ordering::Relaxed)
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Word index and bit mask of a bit position (Lsb0, as in `BloomFilter`).
+    #[inline]
+    fn locate(index: usize) -> (usize, u64) {
+        (index / 64, 1u64 << (index % 64))
+    }
+}
+
+impl From<BloomFilter> for AtomicBloomFilter {
+    fn from(filter: BloomFilter) -> Self {
+        Self {
+            words: filter.words().iter().map(|&w| AtomicU64::new(w)).collect(),
+            num_bits: filter.num_bits(),
+            num_hashes: filter.num_hashes(),
+            seed: filter.seed(),
+            items_inserted: AtomicU64::new(filter.items_inserted()),
+        }
+    }
+}
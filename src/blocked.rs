@@ -0,0 +1,225 @@
+//! Cache-line blocked (split-block) Bloom filter.
+//!
+//! A standard [`BloomFilter`](crate::bloom::BloomFilter) probes `k`
+//! random positions across the whole bit array; at multi-gigabyte
+//! sizes each probe is a cache miss. This variant follows the
+//! split-block design used by Parquet and Impala: a key selects one
+//! 64-byte block (a single cache line), and sets exactly one bit in
+//! each of the block's eight 64-bit lanes. Every operation therefore
+//! touches one cache line.
+//!
+//! The eight lane masks are computed with independent multiply-shift
+//! salts, so insert and lookup are straight-line lane-wise `OR` / `AND`
+//! operations that compile to SIMD on targets that support it.
+//!
+//! Blocking costs a little accuracy: for the same memory the false
+//! positive rate is somewhat higher than a standard filter, so the
+//! constructor sizes the filter from the exact split-block FP model.
+
+use crate::hash::{DEFAULT_SEED, hash_bytes};
+
+/// Number of 64-bit lanes per block (and bits set per key).
+const LANES: usize = 8;
+
+/// Bits per lane.
+const LANE_BITS: f64 = 64.0;
+
+/// Multiply-shift salts, one per lane (from the Parquet specification).
+const SALTS: [u32; LANES] = [
+    0x47b6_137b,
+    0x4497_4d91,
+    0x8824_ad5b,
+    0xa2b7_289d,
+    0x7054_95c7,
+    0x2df1_424b,
+    0x9efc_4947,
+    0x5c6b_fb31,
+];
+
+/// One cache line: eight 64-bit lanes.
+#[derive(Clone, Copy, Default)]
+#[repr(C, align(64))]
+struct Block([u64; LANES]);
+
+/// Split-block Bloom filter with one cache line per key.
+///
+/// # Characteristics
+/// - No false negatives
+/// - One memory access per insert or lookup
+/// - Slightly more memory than a standard filter for the same FP rate
+///
+/// # Fields
+/// - `blocks`: Cache-line aligned blocks
+/// - `seed`: Seed of the stable hash function
+/// - `items_inserted`: Count of inserted elements (n)
+pub struct BlockedBloomFilter {
+    blocks: Vec<Block>,
+    seed: u64,
+    items_inserted: u64,
+}
+
+impl BlockedBloomFilter {
+    /// Create a new blocked Bloom filter using [`DEFAULT_SEED`].
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of elements (n)
+    /// - `fp_rate`: Desired false positive probability (p)
+    pub fn new(capacity: usize, fp_rate: f64) -> Self {
+        Self::with_seed(capacity, fp_rate, DEFAULT_SEED)
+    }
+
+    /// Create a new blocked Bloom filter with explicit hash seed.
+    ///
+    /// The number of blocks is the smallest for which the split-block
+    /// false positive model predicts at most `fp_rate` at `capacity`.
+    ///
+    /// # Panics
+    /// Panics if `fp_rate` is not in `(0.0, 1.0)`, or if reaching it
+    /// takes more than 2^32 blocks.
+    pub fn with_seed(capacity: usize, fp_rate: f64, seed: u64) -> Self {
+        assert!(
+            fp_rate > 0.0 && fp_rate < 1.0,
+            "False positive rate must be in (0, 1)"
+        );
+        let num_blocks = Self::blocks_for(capacity, fp_rate);
+        assert!(
+            num_blocks <= u32::MAX as usize,
+            "Blocked filter is limited to 2^32 blocks"
+        );
+
+        Self {
+            blocks: vec![Block::default(); num_blocks],
+            seed,
+            items_inserted: 0,
+        }
+    }
+
+    /// Insert an element into the filter.
+    pub fn insert(&mut self, value: &str) {
+        let h = hash_bytes(value.as_bytes(), self.seed);
+        let index = self.block_index(h);
+        let mask = Self::mask(h as u32);
+
+        let block = &mut self.blocks[index].0;
+        for (lane, bit) in block.iter_mut().zip(mask) {
+            *lane |= bit;
+        }
+
+        self.items_inserted += 1;
+    }
+
+    /// Check whether an element is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        let h = hash_bytes(value.as_bytes(), self.seed);
+        let mask = Self::mask(h as u32);
+        let block = &self.blocks[self.block_index(h)].0;
+
+        // Branch-free across lanes so the check vectorizes
+        block
+            .iter()
+            .zip(mask)
+            .fold(0u64, |missing, (lane, bit)| missing | (bit & !lane))
+            == 0
+    }
+
+    /// Estimate the current false positive rate from the item count.
+    ///
+    /// Uses the split-block model: with `j` keys in a block, each lane
+    /// bit is set with probability `1 - (1 - 1/64)^j`, and `j` follows
+    /// a Poisson distribution with mean `n / blocks`.
+    pub fn false_positive_rate(&self) -> f64 {
+        split_block_fp(self.items_inserted as f64 / self.blocks.len() as f64)
+    }
+
+    /// Number of 64-byte blocks.
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Size of the bit array, in bits.
+    pub fn num_bits(&self) -> usize {
+        self.blocks.len() * LANES * 64
+    }
+
+    /// Number of insert calls made so far (n).
+    pub fn items_inserted(&self) -> u64 {
+        self.items_inserted
+    }
+
+    /// Seed of the filter's hash function.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Smallest block count whose predicted FP rate is within `fp_rate`.
+    fn blocks_for(capacity: usize, fp_rate: f64) -> usize {
+        let n = capacity.max(1) as f64;
+        let predicted = |blocks: usize| split_block_fp(n / blocks as f64);
+
+        // Stop doubling once past the block limit enforced by the caller
+        let mut hi = 1usize;
+        while predicted(hi) > fp_rate && hi <= u32::MAX as usize {
+            hi *= 2;
+        }
+        let mut lo = hi / 2;
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if predicted(mid) > fp_rate {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        hi
+    }
+
+    /// Map the upper 32 hash bits onto `[0, blocks)` without division.
+    #[inline]
+    fn block_index(&self, h: u64) -> usize {
+        (((h >> 32) * self.blocks.len() as u64) >> 32) as usize
+    }
+
+    /// One bit per lane, chosen by the top 6 bits of `key * salt`.
+    #[inline]
+    fn mask(key: u32) -> [u64; LANES] {
+        let mut mask = [0u64; LANES];
+        for (bit, salt) in mask.iter_mut().zip(SALTS) {
+            *bit = 1u64 << (key.wrapping_mul(salt) >> 26);
+        }
+        mask
+    }
+}
+
+/// Expected split-block FP rate at an average load of `lambda` keys per block.
+fn split_block_fp(lambda: f64) -> f64 {
+    let miss = 1.0 - 1.0 / LANE_BITS;
+    if lambda <= 0.0 {
+        return 0.0;
+    }
+    if lambda > 200.0 {
+        // Overloaded blocks: the Poisson spread no longer matters and
+        // e^-lambda would underflow
+        return (1.0 - miss.powf(lambda)).powi(LANES as i32);
+    }
+
+    // Sum the Poisson distribution far enough into the tail to converge
+    let upper = (lambda + 10.0 * lambda.sqrt() + 20.0).ceil() as u32;
+
+    let mut p_j = (-lambda).exp();
+    let mut fp = 0.0;
+    for j in 0..=upper {
+        if j > 0 {
+            p_j *= lambda / j as f64;
+        }
+        fp += p_j * (1.0 - miss.powi(j as i32)).powi(LANES as i32);
+    }
+    fp
+}
@@ -6,10 +6,53 @@
 //!
 //! Designed for high-throughput URL deduplication in large-scale
 //! web crawlers and indexing systems.
+//!
+//! # On-disk format
+//!
+//! [`BloomFilter::save`] writes a 48-byte little-endian header followed
+//! by the bit array as little-endian `u64` words:
+//!
+//! | Offset | Size | Field                                  |
+//! |--------|------|----------------------------------------|
+//! | 0      | 4    | Magic `KBLM`                           |
+//! | 4      | 2    | Format version                         |
+//! | 6      | 2    | Reserved (zero)                        |
+//! | 8      | 8    | Number of bits (m)                     |
+//! | 16     | 4    | Number of hash functions (k)           |
+//! | 20     | 4    | Reserved (zero)                        |
+//! | 24     | 8    | Hash seed                              |
+//! | 32     | 8    | Items inserted (n)                     |
+//! | 40     | 8    | XXH3 checksum of bytes 0..40 + payload |
 
-use ahash::RandomState;
+use bitvec::order::Lsb0;
 use bitvec::vec::BitVec;
-use std::hash::Hash;
+use std::fmt;
+use std::hash::Hasher;
+use std::io::{self, Read, Write};
+
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::hash::{DEFAULT_SEED, StableHash, StableHasher, hash_bytes};
+use crate::persist::{HeaderReader, PersistError, read_words};
+
+/// Magic tag identifying a serialized Bloom filter.
+pub(crate) const MAGIC: [u8; 4] = *b"KBLM";
+
+/// Current on-disk format version.
+pub(crate) const FORMAT_VERSION: u16 = 1;
+
+/// Size of the serialized header in bytes.
+pub(crate) const HEADER_LEN: usize = 48;
+
+/// Offset of the checksum field; the checksum covers everything before it.
+const CHECKSUM_OFFSET: usize = 40;
+
+/// Items hashed and prefetched together by the batch operations; small
+/// enough that every prefetched line is still cached when probed.
+const BATCH_CHUNK: usize = 32;
+
+/// Probes per item prefetched by [`BloomFilter::contains_batch`].
+const LOOKUP_PREFETCH_PROBES: u32 = 2;
 
 /// Bloom filter for approximate set membership testing.
 ///
@@ -21,17 +64,18 @@ use std::hash::Hash;
 /// # Fields
 /// - `bits`: Bit vector backing the filter
 /// - `num_hashes`: Number of hash functions (k)
-/// - `hash_builder`: Fast, randomized hash builder
+/// - `seed`: Seed of the stable hash functions
 /// - `items_inserted`: Count of inserted elements (n)
+#[derive(Clone)]
 pub struct BloomFilter {
-    bits: BitVec,
+    bits: BitVec<u64, Lsb0>,
     num_hashes: u32,
-    hash_builder: RandomState,
+    seed: u64,
     items_inserted: u64,
 }
 
 impl BloomFilter {
-    /// Create a new Bloom filter.
+    /// Create a new Bloom filter using [`DEFAULT_SEED`].
     ///
     /// # Arguments
     /// - `capacity`: Expected number of elements (n)
@@ -43,15 +87,20 @@ impl BloomFilter {
     /// - Number of hash functions (k):
     ///   k = (m / n) * ln(2)
     pub fn new(capacity: usize, fp_rate: f64) -> Self {
-        let ln2 = std::f64::consts::LN_2;
+        Self::with_seed(capacity, fp_rate, DEFAULT_SEED)
+    }
 
-        let m = (-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as usize;
-        let k = ((m as f64 / capacity as f64) * ln2).ceil() as u32;
+    /// Create a new Bloom filter with explicit hash seed.
+    ///
+    /// Filters built with the same `capacity`, `fp_rate` and `seed` set
+    /// the same bits for the same items in every process.
+    pub fn with_seed(capacity: usize, fp_rate: f64, seed: u64) -> Self {
+        let (m, k) = optimal_params(capacity, fp_rate);
 
         Self {
             bits: BitVec::repeat(false, m),
             num_hashes: k,
-            hash_builder: RandomState::new(),
+            seed,
             items_inserted: 0,
         }
     }
@@ -62,15 +111,8 @@ impl BloomFilter {
     ///
     /// `position_i = (h1 + i * h2) % m`
     pub fn insert(&mut self, value: &str) {
-        let (h1, h2) = self.base_hashes(value);
-        let m = self.bits.len() as u64;
-
-        for i in 0..self.num_hashes {
-            let index = (h1.wrapping_add((i as u64).wrapping_mul(h2)) % m) as usize;
-            self.bits.set(index, true);
-        }
-
-        self.items_inserted += 1;
+        let (h1, h2) = self.hashes_for_bytes(value.as_bytes());
+        self.set_bits(h1, h2);
     }
 
     /// Check whether an element is possibly in the set.
@@ -79,17 +121,318 @@ impl BloomFilter {
     /// - `false` if the element is **definitely not present**
     /// - `true` if the element is **possibly present**
     pub fn contains(&self, value: &str) -> bool {
-        let (h1, h2) = self.base_hashes(value);
+        let (h1, h2) = self.hashes_for_bytes(value.as_bytes());
+        self.check_bits(h1, h2)
+    }
+
+    /// Insert many elements, overlapping their memory accesses.
+    ///
+    /// Equivalent to calling [`insert`](Self::insert) on each element
+    /// in order, but hashes a chunk of elements and prefetches every
+    /// bit they touch before setting any, so cache misses on a large
+    /// filter are serviced in parallel instead of one at a time.
+    pub fn insert_batch(&mut self, values: &[&str]) {
+        let k = self.num_hashes as usize;
+        let mut positions = Vec::with_capacity(BATCH_CHUNK * k);
+        for chunk in values.chunks(BATCH_CHUNK) {
+            self.probe_and_prefetch(chunk, &mut positions);
+            for item in positions.chunks_exact(k) {
+                self.set_positions(item);
+            }
+        }
+    }
+
+    /// Check many elements, overlapping their memory accesses.
+    ///
+    /// Returns one [`contains`](Self::contains) result per element.
+    pub fn contains_batch(&self, values: &[&str]) -> Vec<bool> {
         let m = self.bits.len() as u64;
+        let words = self.bits.as_raw_slice();
+        let mut out = Vec::with_capacity(values.len());
+        let mut hashes = Vec::with_capacity(BATCH_CHUNK);
+        for chunk in values.chunks(BATCH_CHUNK) {
+            // Most absent items are rejected by their first probes, so
+            // only those are prefetched
+            hashes.clear();
+            for value in chunk {
+                let (h1, h2) = self.hashes_for_bytes(value.as_bytes());
+                for i in 0..self.num_hashes.min(LOOKUP_PREFETCH_PROBES) {
+                    prefetch(&words[probe_index(h1, h2, i, m) / 64]);
+                }
+                hashes.push((h1, h2));
+            }
+            out.extend(hashes.iter().map(|&(h1, h2)| self.check_bits(h1, h2)));
+        }
+        out
+    }
 
-        for i in 0..self.num_hashes {
-            let index = (h1.wrapping_add((i as u64).wrapping_mul(h2)) % m) as usize;
-            if !self.bits[index] {
-                return false;
+    /// Check and insert many elements, overlapping their memory accesses.
+    ///
+    /// Elements are processed in order, so a value repeated within the
+    /// batch is reported as present from its second occurrence on.
+    ///
+    /// # Returns
+    /// One entry per element: `true` if it was possibly present (and
+    /// was not inserted), `false` if it was absent and is now inserted.
+    pub fn check_and_insert_batch(&mut self, values: &[&str]) -> Vec<bool> {
+        let k = self.num_hashes as usize;
+        let mut out = Vec::with_capacity(values.len());
+        let mut positions = Vec::with_capacity(BATCH_CHUNK * k);
+        for chunk in values.chunks(BATCH_CHUNK) {
+            self.probe_and_prefetch(chunk, &mut positions);
+            for item in positions.chunks_exact(k) {
+                let present = self.check_positions(item);
+                if !present {
+                    self.set_positions(item);
+                }
+                out.push(present);
             }
         }
+        out
+    }
 
-        true
+    /// Insert a raw byte string.
+    ///
+    /// Equivalent to [`insert`](Self::insert) for the UTF-8 bytes of a `&str`.
+    pub fn insert_bytes(&mut self, value: &[u8]) {
+        let (h1, h2) = self.hashes_for_bytes(value);
+        self.set_bits(h1, h2);
+    }
+
+    /// Check whether a raw byte string is possibly in the set.
+    pub fn contains_bytes(&self, value: &[u8]) -> bool {
+        let (h1, h2) = self.hashes_for_bytes(value);
+        self.check_bits(h1, h2)
+    }
+
+    /// Insert any hashable value, e.g. a `u64` fingerprint, a
+    /// [`SimHash`](crate::simhash::SimHash) or a `(host, path)` tuple.
+    ///
+    /// The value is fed through its [`StableHash`] impl into the seeded
+    /// [`StableHasher`], so it does not need to be formatted into a
+    /// string first, and hashes the same in every Rust release. Values
+    /// must be queried with
+    /// [`contains_hashable`](Self::contains_hashable): the hashable,
+    /// byte and string variants are not interchangeable.
+    pub fn insert_hashable<T: StableHash + ?Sized>(&mut self, value: &T) {
+        let (h1, h2) = self.base_hashes(value);
+        self.set_bits(h1, h2);
+    }
+
+    /// Check whether a hashable value is possibly in the set.
+    pub fn contains_hashable<T: StableHash + ?Sized>(&self, value: &T) -> bool {
+        let (h1, h2) = self.base_hashes(value);
+        self.check_bits(h1, h2)
+    }
+
+    /// Insert an item by its two precomputed base hashes.
+    ///
+    /// For callers that already hold a good 128-bit fingerprint of the
+    /// item. `h1` and `h2` should be independent; bit `i` is set at
+    /// `(h1 + i * h2) % m`.
+    pub fn insert_prehashed(&mut self, h1: u64, h2: u64) {
+        self.set_bits(h1, h2);
+    }
+
+    /// Check whether an item given by its two base hashes is possibly in the set.
+    pub fn contains_prehashed(&self, h1: u64, h2: u64) -> bool {
+        self.check_bits(h1, h2)
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Size of the bit array (m).
+    pub fn num_bits(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Number of hash functions (k).
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Number of insert calls made so far (n).
+    ///
+    /// Re-inserts of present items are counted too; see
+    /// [`estimated_cardinality`](Self::estimated_cardinality) for the
+    /// number of distinct items.
+    pub fn items_inserted(&self) -> u64 {
+        self.items_inserted
+    }
+
+    /// Reset every bit and the insert count, keeping size and seed.
+    pub fn clear(&mut self) {
+        self.bits.fill(false);
+        self.items_inserted = 0;
+    }
+
+    /// Fraction of bits currently set (X / m).
+    ///
+    /// Counts every bit, so the cost is linear in m.
+    pub fn fill_ratio(&self) -> f64 {
+        self.bits.count_ones() as f64 / self.bits.len() as f64
+    }
+
+    /// Estimate the number of distinct items from the fill ratio
+    /// (Swamidass–Baldi):
+    ///
+    /// `n ≈ -(m / k) * ln(1 - X / m)`, where X is the number of set bits.
+    ///
+    /// Unlike [`items_inserted`](Self::items_inserted) this ignores
+    /// re-inserts and stays meaningful for merged or loaded filters.
+    /// Returns infinity for a completely full filter.
+    pub fn estimated_cardinality(&self) -> f64 {
+        cardinality_from_fill(self.fill_ratio(), self.bits.len(), self.num_hashes)
+    }
+
+    /// Check that `other` uses the same m, k and seed, so the two
+    /// filters can be combined bit by bit.
+    pub fn check_compatible(&self, other: &BloomFilter) -> Result<(), MergeError> {
+        if self.bits.len() != other.bits.len() {
+            return Err(MergeError::BitCountMismatch {
+                left: self.bits.len(),
+                right: other.bits.len(),
+            });
+        }
+        if self.num_hashes != other.num_hashes {
+            return Err(MergeError::HashCountMismatch {
+                left: self.num_hashes,
+                right: other.num_hashes,
+            });
+        }
+        if self.seed != other.seed {
+            return Err(MergeError::SeedMismatch {
+                left: self.seed,
+                right: other.seed,
+            });
+        }
+        Ok(())
+    }
+
+    /// Merge `other` into this filter (bitwise OR).
+    ///
+    /// The result contains every item of either filter. Because the
+    /// filters may share items, the item count is re-estimated from
+    /// the merged fill ratio instead of summing both counts.
+    pub fn union_with(&mut self, other: &BloomFilter) -> Result<(), MergeError> {
+        self.check_compatible(other)?;
+
+        for (a, b) in self
+            .bits
+            .as_raw_mut_slice()
+            .iter_mut()
+            .zip(other.bits.as_raw_slice())
+        {
+            *a |= *b;
+        }
+
+        self.items_inserted = count_from_estimate(self.estimated_cardinality());
+        Ok(())
+    }
+
+    /// Allocating variant of [`union_with`](Self::union_with).
+    pub fn union(&self, other: &BloomFilter) -> Result<BloomFilter, MergeError> {
+        let mut out = self.clone();
+        out.union_with(other)?;
+        Ok(out)
+    }
+
+    /// Keep only bits set in both filters (bitwise AND).
+    ///
+    /// Every item present in both filters is still reported present.
+    /// The item count is estimated by inclusion–exclusion,
+    /// `|A ∩ B| = |A| + |B| - |A ∪ B|`, which is far more accurate than
+    /// the fill ratio of the AND-ed bits.
+    pub fn intersect_with(&mut self, other: &BloomFilter) -> Result<(), MergeError> {
+        self.check_compatible(other)?;
+
+        let n_a = self.estimated_cardinality();
+        let n_b = other.estimated_cardinality();
+
+        // Count the bits of `a | b` while AND-ing, masking the unused
+        // tail of the last word, so the union is never materialized
+        let num_bits = self.bits.len();
+        let mut union_ones = 0usize;
+        for (i, (a, b)) in self
+            .bits
+            .as_raw_mut_slice()
+            .iter_mut()
+            .zip(other.bits.as_raw_slice())
+            .enumerate()
+        {
+            let live = num_bits - i * 64;
+            let mask = if live >= 64 {
+                u64::MAX
+            } else {
+                (1 << live) - 1
+            };
+            union_ones += ((*a | *b) & mask).count_ones() as usize;
+            *a &= *b;
+        }
+        let n_union = cardinality_from_fill(
+            union_ones as f64 / num_bits as f64,
+            num_bits,
+            self.num_hashes,
+        );
+
+        let by_inclusion = (n_a + n_b - n_union).max(0.0);
+        let by_fill = self.estimated_cardinality();
+        self.items_inserted = count_from_estimate(by_inclusion.min(by_fill));
+        Ok(())
+    }
+
+    /// Allocating variant of [`intersect_with`](Self::intersect_with).
+    pub fn intersect(&self, other: &BloomFilter) -> Result<BloomFilter, MergeError> {
+        let mut out = self.clone();
+        out.intersect_with(other)?;
+        Ok(out)
+    }
+
+    /// Serialize the filter in the versioned on-disk format.
+    ///
+    /// The writer is not buffered internally; wrap files in a
+    /// `BufWriter`.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let words = self.bits.as_raw_slice();
+
+        let mut header = BloomHeader {
+            num_bits: self.bits.len() as u64,
+            num_hashes: self.num_hashes,
+            seed: self.seed,
+            items_inserted: self.items_inserted,
+            checksum: 0,
+        };
+        header.checksum = header.compute_checksum(words);
+
+        writer.write_all(&header.encode())?;
+        for_each_le_chunk(words, |chunk| writer.write_all(chunk))?;
+        writer.flush()
+    }
+
+    /// Deserialize a filter written by [`save`](Self::save).
+    ///
+    /// Rejects files with a wrong magic tag, an unsupported version, an
+    /// inconsistent header or a checksum mismatch.
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, PersistError> {
+        let mut raw = [0u8; HEADER_LEN];
+        reader.read_exact(&mut raw)?;
+        let header = BloomHeader::decode(&raw)?;
+
+        let words = read_words(&mut reader, header.num_words())?;
+        if header.compute_checksum(&words) != header.checksum {
+            return Err(PersistError::ChecksumMismatch);
+        }
+
+        Ok(Self::from_words(
+            words,
+            header.num_bits as usize,
+            header.num_hashes,
+            header.seed,
+            header.items_inserted,
+        ))
     }
 
     /// Estimate the current false positive rate.
@@ -104,11 +447,306 @@ impl BloomFilter {
         (1.0 - (-k * n / m).exp()).powf(k)
     }
 
-    /// Generate two base hashes for double hashing.
+    /// Current false positive rate measured from the actual fill ratio.
+    ///
+    /// Formula:
+    /// `(X / m)^k`
+    ///
+    /// Exact for the filter as it is now, independent of how many
+    /// inserts were counted.
+    pub fn false_positive_rate_from_fill(&self) -> f64 {
+        self.fill_ratio().powi(self.num_hashes as i32)
+    }
+
+    /// Rebuild a filter from its raw `u64` words (Lsb0 bit order).
+    pub(crate) fn from_words(
+        words: Vec<u64>,
+        num_bits: usize,
+        num_hashes: u32,
+        seed: u64,
+        items_inserted: u64,
+    ) -> Self {
+        let mut bits = BitVec::from_vec(words);
+        bits.truncate(num_bits);
+
+        Self {
+            bits,
+            num_hashes,
+            seed,
+            items_inserted,
+        }
+    }
+
+    /// Raw `u64` words backing the bit array (Lsb0 bit order).
+    pub(crate) fn words(&self) -> &[u64] {
+        self.bits.as_raw_slice()
+    }
+
+    /// Set the `k` bits selected by a pair of base hashes.
     #[inline]
-    fn base_hashes<T: Hash>(&self, value: T) -> (u64, u64) {
-        let h1 = self.hash_builder.hash_one(value);
-        let h2 = self.hash_builder.hash_one(h1);
-        (h1, h2)
+    fn set_bits(&mut self, h1: u64, h2: u64) {
+        let m = self.bits.len() as u64;
+
+        for i in 0..self.num_hashes {
+            self.bits.set(probe_index(h1, h2, i, m), true);
+        }
+
+        self.items_inserted += 1;
+    }
+
+    /// Check the `k` bits selected by a pair of base hashes.
+    #[inline]
+    fn check_bits(&self, h1: u64, h2: u64) -> bool {
+        let m = self.bits.len() as u64;
+
+        for i in 0..self.num_hashes {
+            if !self.bits[probe_index(h1, h2, i, m)] {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Compute the `k` probe positions of each element of a chunk into
+    /// `positions` and prefetch the word holding each of them.
+    fn probe_and_prefetch(&self, chunk: &[&str], positions: &mut Vec<usize>) {
+        let m = self.bits.len() as u64;
+        let words = self.bits.as_raw_slice();
+
+        positions.clear();
+        for value in chunk {
+            let (h1, h2) = self.hashes_for_bytes(value.as_bytes());
+            for i in 0..self.num_hashes {
+                let position = probe_index(h1, h2, i, m);
+                prefetch(&words[position / 64]);
+                positions.push(position);
+            }
+        }
+    }
+
+    /// Set precomputed probe positions of one element.
+    #[inline]
+    fn set_positions(&mut self, positions: &[usize]) {
+        let words = self.bits.as_raw_mut_slice();
+        for &position in positions {
+            words[position / 64] |= 1 << (position % 64);
+        }
+
+        self.items_inserted += 1;
+    }
+
+    /// Check precomputed probe positions of one element.
+    #[inline]
+    fn check_positions(&self, positions: &[usize]) -> bool {
+        let words = self.bits.as_raw_slice();
+        positions
+            .iter()
+            .all(|&position| words[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// Generate two base hashes for double hashing from raw bytes.
+    #[inline]
+    fn hashes_for_bytes(&self, bytes: &[u8]) -> (u64, u64) {
+        hash_pair(bytes, self.seed)
+    }
+
+    /// Generate two base hashes for double hashing from any hashable value.
+    #[inline]
+    fn base_hashes<T: StableHash + ?Sized>(&self, value: &T) -> (u64, u64) {
+        hashable_pair(value, self.seed)
+    }
+}
+
+/// Swamidass–Baldi cardinality estimate for a filter with the given fill ratio.
+pub(crate) fn cardinality_from_fill(fill: f64, num_bits: usize, num_hashes: u32) -> f64 {
+    let m = num_bits as f64;
+    let k = num_hashes as f64;
+
+    -(m / k) * (1.0 - fill).ln()
+}
+
+/// Round a cardinality estimate to an item count (saturating).
+fn count_from_estimate(estimate: f64) -> u64 {
+    estimate.round() as u64
+}
+
+/// Reasons two filters cannot be combined.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// The filters have different bit array sizes (m).
+    BitCountMismatch { left: usize, right: usize },
+    /// The filters use different numbers of hash functions (k).
+    HashCountMismatch { left: u32, right: u32 },
+    /// The filters use different hash seeds.
+    SeedMismatch { left: u64, right: u64 },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::BitCountMismatch { left, right } => {
+                write!(f, "bit counts differ ({} vs {})", left, right)
+            }
+            MergeError::HashCountMismatch { left, right } => {
+                write!(f, "hash function counts differ ({} vs {})", left, right)
+            }
+            MergeError::SeedMismatch { left, right } => {
+                write!(f, "hash seeds differ ({:#x} vs {:#x})", left, right)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Optimal bit count (m) and hash count (k) for `capacity` items at `fp_rate`.
+pub(crate) fn optimal_params(capacity: usize, fp_rate: f64) -> (usize, u32) {
+    let ln2 = std::f64::consts::LN_2;
+
+    let m = (-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as usize;
+    let k = ((m as f64 / capacity as f64) * ln2).ceil() as u32;
+    (m, k)
+}
+
+/// Generate the two double-hashing base hashes of a byte string.
+///
+/// Shared by every filter that must stay bit-compatible with
+/// [`BloomFilter`].
+#[inline]
+pub(crate) fn hash_pair(bytes: &[u8], seed: u64) -> (u64, u64) {
+    let h1 = hash_bytes(bytes, seed);
+    (h1, hash_bytes(&h1.to_le_bytes(), seed))
+}
+
+/// [`hash_pair`] for a [`StableHash`] value instead of raw bytes.
+#[inline]
+pub(crate) fn hashable_pair<T: StableHash + ?Sized>(value: &T, seed: u64) -> (u64, u64) {
+    let mut hasher = StableHasher::with_seed(seed);
+    value.stable_hash(&mut hasher);
+    let h1 = hasher.finish();
+    (h1, hash_bytes(&h1.to_le_bytes(), seed))
+}
+
+/// Bit position of the `i`-th probe: `(h1 + i * h2) % m`.
+#[inline]
+pub(crate) fn probe_index(h1: u64, h2: u64, i: u32, m: u64) -> usize {
+    (h1.wrapping_add((i as u64).wrapping_mul(h2)) % m) as usize
+}
+
+/// Hint the CPU to pull the cache line holding `value` into L1.
+///
+/// A no-op on targets without a stable prefetch instruction.
+#[inline(always)]
+pub(crate) fn prefetch<T>(value: &T) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+        // SAFETY: prefetching is a hint and never faults, whatever the address.
+        unsafe { _mm_prefetch::<_MM_HINT_T0>((value as *const T).cast()) };
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = value;
+}
+
+/// Decoded form of the on-disk header.
+pub(crate) struct BloomHeader {
+    pub(crate) num_bits: u64,
+    pub(crate) num_hashes: u32,
+    pub(crate) seed: u64,
+    pub(crate) items_inserted: u64,
+    pub(crate) checksum: u64,
+}
+
+impl BloomHeader {
+    pub(crate) fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[0..4].copy_from_slice(&MAGIC);
+        out[4..6].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out[8..16].copy_from_slice(&self.num_bits.to_le_bytes());
+        out[16..20].copy_from_slice(&self.num_hashes.to_le_bytes());
+        out[24..32].copy_from_slice(&self.seed.to_le_bytes());
+        out[32..40].copy_from_slice(&self.items_inserted.to_le_bytes());
+        out[40..48].copy_from_slice(&self.checksum.to_le_bytes());
+        out
+    }
+
+    pub(crate) fn decode(raw: &[u8; HEADER_LEN]) -> Result<Self, PersistError> {
+        let mut r = HeaderReader::new(raw);
+
+        if r.bytes::<4>() != MAGIC {
+            return Err(PersistError::BadMagic);
+        }
+        let version = r.u16();
+        if version != FORMAT_VERSION {
+            return Err(PersistError::UnsupportedVersion(version));
+        }
+        r.u16();
+
+        let num_bits = r.u64();
+        let num_hashes = r.u32();
+        r.u32();
+        let header = Self {
+            num_bits,
+            num_hashes,
+            seed: r.u64(),
+            items_inserted: r.u64(),
+            checksum: r.u64(),
+        };
+
+        if header.num_bits == 0 || header.num_bits > usize::MAX as u64 {
+            return Err(PersistError::Corrupt("invalid bit count"));
+        }
+        if header.num_hashes == 0 {
+            return Err(PersistError::Corrupt("zero hash functions"));
+        }
+        Ok(header)
+    }
+
+    /// Number of `u64` words in the payload.
+    pub(crate) fn num_words(&self) -> u64 {
+        self.num_bits.div_ceil(64)
+    }
+
+    /// Checksum over the header fields preceding the checksum and the payload.
+    pub(crate) fn compute_checksum(&self, words: &[u64]) -> u64 {
+        let mut hasher = self.checksum_hasher();
+        for_each_le_chunk(words, |chunk| {
+            hasher.update(chunk);
+            Ok(())
+        })
+        .expect("hashing cannot fail");
+        hasher.digest()
+    }
+
+    /// Checksum over the header and a payload given as raw bytes.
+    pub(crate) fn compute_checksum_bytes(&self, payload: &[u8]) -> u64 {
+        let mut hasher = self.checksum_hasher();
+        hasher.update(payload);
+        hasher.digest()
+    }
+
+    /// Hasher primed with the header fields covered by the checksum.
+    fn checksum_hasher(&self) -> Xxh3 {
+        let mut hasher = Xxh3::new();
+        hasher.update(&self.encode()[..CHECKSUM_OFFSET]);
+        hasher
+    }
+}
+
+/// Feed `words` to `f` as little-endian byte chunks.
+fn for_each_le_chunk<F>(words: &[u64], mut f: F) -> io::Result<()>
+where
+    F: FnMut(&[u8]) -> io::Result<()>,
+{
+    const CHUNK_WORDS: usize = 8192;
+
+    let mut buf = [0u8; CHUNK_WORDS * 8];
+    for chunk in words.chunks(CHUNK_WORDS) {
+        for (dst, word) in buf.chunks_exact_mut(8).zip(chunk) {
+            dst.copy_from_slice(&word.to_le_bytes());
+        }
+        f(&buf[..chunk.len() * 8])?;
     }
+    Ok(())
 }
@@ -0,0 +1,195 @@
+//! Thread-safe deduplication engine.
+//!
+//! [`DeduplicationEngine::check_and_insert`](crate::DeduplicationEngine::check_and_insert)
+//! takes `&mut self`, so sharing it between crawler threads means
+//! wrapping the whole engine in a `Mutex`. [`ConcurrentDeduplicationEngine`]
+//! takes `&self` everywhere and is meant to be shared through an `Arc`.
+//!
+//! Membership lives in an [`AtomicBloomFilter`]. Setting `k` bits with
+//! `fetch_or` is not atomic as a whole, so two threads inserting the
+//! same new URL could both see "new". To make check-and-insert
+//! linearizable per URL, the URL's hash also selects one of a fixed set
+//! of striped locks; threads submitting the same URL serialize on that
+//! stripe while unrelated URLs proceed in parallel.
+
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+
+use crate::atomic::AtomicBloomFilter;
+use crate::bloom::hash_pair;
+use crate::engine::{Config, ConfigError, EngineStatsSnapshot, FilterKind, Stats};
+use crate::lshbloom::{AtomicLSHBloom, LSHBloom};
+use crate::normalizer::UrlNormalizer;
+use crate::simhash::SimHashEngine;
+
+/// Number of lock stripes guarding check-and-insert.
+const LOCK_STRIPES: usize = 1024;
+
+/// Deduplication engine whose operations take `&self`.
+///
+/// # Characteristics
+/// - `Send + Sync`; share it with `Arc` across threads or async tasks
+/// - `check_and_insert` is linearizable: of N concurrent submissions of
+///   the same new URL, exactly one is reported as new
+/// - Only [`FilterKind::Bloom`] is supported
+///
+/// # Fields
+/// - `filter`: Lock-free membership filter
+/// - `stripes`: Locks serializing check-and-insert per URL hash
+/// - `normalizer`: URL normalizer
+/// - `near_duplicates`: SimHash engine and LSH index, if enabled
+/// - `stats`: Atomic counters
+/// - `config`: Configuration the engine was built from
+pub struct ConcurrentDeduplicationEngine {
+    filter: AtomicBloomFilter,
+    stripes: Box<[Mutex<()>]>,
+    normalizer: UrlNormalizer,
+    near_duplicates: Option<SharedNearDuplicateIndex>,
+    stats: Stats,
+    config: Config,
+}
+
+/// SimHash engine paired with a lock-free LSHBloom index.
+struct SharedNearDuplicateIndex {
+    simhash: SimHashEngine,
+    lsh: AtomicLSHBloom,
+}
+
+impl ConcurrentDeduplicationEngine {
+    /// Create a new concurrent engine from a validated configuration.
+    ///
+    /// Returns [`ConfigError::UnsupportedFilter`] unless
+    /// `config.filter` is [`FilterKind::Bloom`], and
+    /// [`ConfigError::UnsupportedVerification`] if
+    /// `config.verification` is set.
+    pub fn new(config: Config) -> Result<Self, ConfigError> {
+        config.validate()?;
+        if config.filter != FilterKind::Bloom {
+            return Err(ConfigError::UnsupportedFilter(config.filter));
+        }
+        if config.verification.is_some() {
+            return Err(ConfigError::UnsupportedVerification);
+        }
+
+        let near_duplicates = config.simhash_enabled.then(|| SharedNearDuplicateIndex {
+            simhash: SimHashEngine::with_seed(64, config.seed),
+            lsh: AtomicLSHBloom::with_seed(
+                config.capacity,
+                config.false_positive_rate,
+                LSHBloom::bands_for_capacity(
+                    config.similarity_threshold,
+                    config.capacity,
+                    config.false_positive_rate,
+                ),
+                config.seed,
+            ),
+        });
+
+        Ok(Self {
+            filter: AtomicBloomFilter::with_seed(
+                config.capacity,
+                config.false_positive_rate,
+                config.seed,
+            ),
+            stripes: (0..LOCK_STRIPES).map(|_| Mutex::new(())).collect(),
+            normalizer: UrlNormalizer::with_config(config.normalizer.clone()),
+            near_duplicates,
+            stats: Stats::default(),
+            config,
+        })
+    }
+
+    /// Replace the normalizer built from `config.normalizer`.
+    ///
+    /// Use this to carry over domain rules and extra tracking parameters.
+    pub fn with_normalizer(mut self, normalizer: UrlNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Normalize, check, and insert a URL.
+    ///
+    /// New URLs are also indexed for near-duplicate detection when
+    /// SimHash is enabled.
+    ///
+    /// # Returns
+    /// - `Ok(false)` → URL is new
+    /// - `Ok(true)` → URL is a duplicate
+    pub fn check_and_insert(&self, url: &str) -> Result<bool, url::ParseError> {
+        self.stats.total_checked.fetch_add(1, Ordering::Relaxed);
+
+        let normalized = self.normalizer.normalize(url)?;
+        let (h1, h2) = hash_pair(normalized.as_bytes(), self.config.seed);
+
+        // Fast path: already-seen URLs never need the lock
+        let duplicate = self.filter.contains_prehashed(h1, h2) || {
+            let _guard = self.stripe(h1).lock().unwrap_or_else(|e| e.into_inner());
+            self.filter.insert_prehashed(h1, h2)
+        };
+
+        if duplicate {
+            self.stats.duplicates_found.fetch_add(1, Ordering::Relaxed);
+            return Ok(true);
+        }
+
+        if let Some(index) = &self.near_duplicates
+            && let Ok(hash) = index.simhash.try_compute_hash_from_url(&normalized)
+        {
+            index.lsh.insert(hash);
+        }
+        self.stats.urls_inserted.fetch_add(1, Ordering::Relaxed);
+        Ok(false)
+    }
+
+    /// Check whether a URL is a duplicate without inserting it.
+    pub fn is_duplicate(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+        Ok(self.filter.contains(&normalized))
+    }
+
+    /// Check whether a URL is similar to a previously inserted URL.
+    ///
+    /// Always `Ok(false)` when SimHash is disabled, or when a domain
+    /// rule normalizes the URL into something that is not a URL.
+    pub fn is_near_duplicate(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+
+        Ok(match &self.near_duplicates {
+            Some(index) => index
+                .simhash
+                .try_compute_hash_from_url(&normalized)
+                .is_ok_and(|hash| index.lsh.query_similar(hash)),
+            None => false,
+        })
+    }
+
+    /// Normalize a URL with the engine's normalizer.
+    pub fn normalize(&self, url: &str) -> Result<String, url::ParseError> {
+        self.normalizer.normalize(url)
+    }
+
+    /// Estimated current false-positive rate of the membership filter.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.filter.false_positive_rate()
+    }
+
+    /// Configuration the engine was built from.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Access internal statistics (read-only).
+    pub fn stats(&self) -> EngineStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Lock stripe owning a URL hash.
+    #[inline]
+    fn stripe(&self, h1: u64) -> &Mutex<()> {
+        &self.stripes[(h1 % self.stripes.len() as u64) as usize]
+    }
+}
@@ -0,0 +1,198 @@
+//! Counting Bloom filter with deletion support.
+//!
+//! Replaces each bit of a [`BloomFilter`](crate::bloom::BloomFilter)
+//! with a 4-bit counter, so items can be removed again (e.g. when a
+//! page returns 404 or its recrawl window expires). Counters are packed
+//! two per byte, so the filter costs 4x the memory of a plain filter
+//! with the same m.
+//!
+//! A counter that reaches 15 saturates: it is never decremented again,
+//! which keeps the "no false negatives" guarantee at the cost of items
+//! sharing that counter never fully disappearing. Saturation events are
+//! counted and exposed through [`CountingBloomFilter::overflow_count`].
+
+use crate::bloom::{hash_pair, optimal_params, probe_index};
+use crate::hash::DEFAULT_SEED;
+
+/// Largest value a 4-bit counter can hold.
+const COUNTER_MAX: u8 = 0x0F;
+
+/// Bloom filter with 4-bit counters and `remove`.
+///
+/// # Characteristics
+/// - No false negatives, including after removals
+/// - Same false positive rate as a `BloomFilter` of equal m and k
+/// - 4 bits of memory per position
+///
+/// # Fields
+/// - `counters`: Packed 4-bit counters, two per byte
+/// - `num_counters`: Number of counters (m)
+/// - `num_hashes`: Number of hash functions (k)
+/// - `seed`: Seed of the stable hash functions
+/// - `items`: Inserts minus successful removals (n)
+/// - `overflows`: Number of counters that saturated
+pub struct CountingBloomFilter {
+    counters: Vec<u8>,
+    num_counters: usize,
+    num_hashes: u32,
+    seed: u64,
+    items: u64,
+    overflows: u64,
+}
+
+impl CountingBloomFilter {
+    /// Create a new counting Bloom filter using [`DEFAULT_SEED`].
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of elements (n)
+    /// - `fp_rate`: Desired false positive probability (p)
+    pub fn new(capacity: usize, fp_rate: f64) -> Self {
+        Self::with_seed(capacity, fp_rate, DEFAULT_SEED)
+    }
+
+    /// Create a new counting Bloom filter with explicit hash seed.
+    pub fn with_seed(capacity: usize, fp_rate: f64, seed: u64) -> Self {
+        let (m, k) = optimal_params(capacity, fp_rate);
+
+        Self {
+            counters: vec![0; m.div_ceil(2)],
+            num_counters: m,
+            num_hashes: k,
+            seed,
+            items: 0,
+            overflows: 0,
+        }
+    }
+
+    /// Insert an element, incrementing its `k` counters.
+    pub fn insert(&mut self, value: &str) {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.seed);
+        let m = self.num_counters as u64;
+
+        for i in 0..self.num_hashes {
+            let index = probe_index(h1, h2, i, m);
+            match self.counter(index) {
+                COUNTER_MAX => {}
+                c => {
+                    if c + 1 == COUNTER_MAX {
+                        self.overflows += 1;
+                    }
+                    self.set_counter(index, c + 1);
+                }
+            }
+        }
+
+        self.items += 1;
+    }
+
+    /// Check whether an element is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.seed);
+        let m = self.num_counters as u64;
+
+        (0..self.num_hashes).all(|i| self.counter(probe_index(h1, h2, i, m)) > 0)
+    }
+
+    /// Remove an element, decrementing its `k` counters.
+    ///
+    /// Only remove items that were actually inserted: removing a false
+    /// positive decrements counters owned by other items and can cause
+    /// false negatives.
+    ///
+    /// # Returns
+    /// - `true` → the element was possibly present and was removed
+    /// - `false` → the element was definitely not present
+    pub fn remove(&mut self, value: &str) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+
+        let (h1, h2) = hash_pair(value.as_bytes(), self.seed);
+        let m = self.num_counters as u64;
+
+        for i in 0..self.num_hashes {
+            let index = probe_index(h1, h2, i, m);
+            match self.counter(index) {
+                // Saturated counters no longer know their true count
+                COUNTER_MAX | 0 => {}
+                c => self.set_counter(index, c - 1),
+            }
+        }
+
+        self.items = self.items.saturating_sub(1);
+        true
+    }
+
+    /// Number of counters that have saturated at 15.
+    ///
+    /// A non-zero value means some removals are incomplete; if it grows
+    /// large, the filter is overloaded and should be rebuilt larger.
+    pub fn overflow_count(&self) -> u64 {
+        self.overflows
+    }
+
+    /// Whether any counter has saturated.
+    pub fn has_overflowed(&self) -> bool {
+        self.overflows > 0
+    }
+
+    /// Estimate the current false positive rate.
+    ///
+    /// Formula:
+    /// `(1 - e^(-k * n / m))^k`
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        let n = self.items as f64;
+        let m = self.num_counters as f64;
+
+        (1.0 - (-k * n / m).exp()).powf(k)
+    }
+
+    /// Number of items currently held (inserts minus removals).
+    pub fn items_inserted(&self) -> u64 {
+        self.items
+    }
+
+    /// Number of counters (m).
+    pub fn num_counters(&self) -> usize {
+        self.num_counters
+    }
+
+    /// Number of hash functions (k).
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    #[inline]
+    fn counter(&self, index: usize) -> u8 {
+        let byte = self.counters[index >> 1];
+        if index & 1 == 0 {
+            byte & 0x0F
+        } else {
+            byte >> 4
+        }
+    }
+
+    #[inline]
+    fn set_counter(&mut self, index: usize, value: u8) {
+        let byte = &mut self.counters[index >> 1];
+        if index & 1 == 0 {
+            *byte = (*byte & 0xF0) | value;
+        } else {
+            *byte = (*byte & 0x0F) | (value << 4);
+        }
+    }
+}
@@ -1 +1,727 @@
+//! Deduplication engine and its configuration.
+//!
+//! The engine combines URL normalization, exact-match Bloom filtering
+//! and (optionally) SimHash-based near-duplicate detection. All sizing
+//! and behavior is driven by a single [`Config`] so every crawler
+//! service configures deduplication the same way.
 
+use std::fmt;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::bloom::BloomFilter;
+use crate::counting::CountingBloomFilter;
+use crate::cuckoo::CuckooFilter;
+use crate::filter::{FilterFull, MembershipFilter};
+use crate::hash::DEFAULT_SEED;
+use crate::lshbloom::LSHBloom;
+use crate::normalizer::{NormalizerConfig, UrlNormalizer};
+use crate::scalable::ScalableBloomFilter;
+use crate::simhash::SimHashEngine;
+use crate::verify::{FingerprintStore, FingerprintWidth, MemoryFingerprintStore, fingerprint};
+use crate::windowed::{Rotation, WindowedBloomFilter};
+
+/// Membership filter backing the engine's exact-match check.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FilterKind {
+    /// Fixed-size [`BloomFilter`] sized for `capacity`.
+    #[default]
+    Bloom,
+    /// [`ScalableBloomFilter`] whose first stage holds `capacity` URLs
+    /// and which grows while keeping the false-positive bound.
+    Scalable,
+    /// [`CountingBloomFilter`] sized for `capacity`; enables
+    /// [`DeduplicationEngine::forget`].
+    Counting,
+    /// [`CuckooFilter`] sized for `capacity`; enables
+    /// [`DeduplicationEngine::forget`] with less memory than `Counting`.
+    ///
+    /// Its false-positive rate is fixed by the fingerprint size
+    /// (about 0.012%), so `false_positive_rate` is ignored. Inserts
+    /// fail with [`EngineError::FilterFull`] once the filter is full.
+    Cuckoo,
+    /// [`WindowedBloomFilter`] that forgets URLs after a window, so
+    /// they are reported as new again and can be recrawled.
+    ///
+    /// `capacity` is the number of unique URLs expected per window;
+    /// with [`Rotation::Inserts`] each generation is sized for its span
+    /// instead.
+    Windowed {
+        /// Number of generations in the ring (G).
+        generations: usize,
+        /// Span of one generation (window / G).
+        rotation: Rotation,
+    },
+}
+
+/// Deduplication engine configuration.
+///
+/// Built fluently from [`Config::default`]:
+///
+/// ```
+/// use kaka::Config;
+///
+/// let config = Config::default()
+///     .with_capacity(10_000_000)
+///     .with_false_positive_rate(0.01)
+///     .disable_simhash();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Expected number of unique URLs (n).
+    pub capacity: usize,
+    /// Desired Bloom filter false-positive rate (p).
+    pub false_positive_rate: f64,
+    /// Membership filter used for exact-match deduplication.
+    pub filter: FilterKind,
+    /// Width of the fingerprints that confirm filter positives, or
+    /// `None` to trust the filter alone.
+    pub verification: Option<FingerprintWidth>,
+    /// URL normalization flags.
+    pub normalizer: NormalizerConfig,
+    /// Whether SimHash near-duplicate detection is enabled.
+    pub simhash_enabled: bool,
+    /// Similarity in `(0.0, 1.0]` above which URLs count as near-duplicates.
+    ///
+    /// Met as closely as `capacity` allows: a large index needs wide
+    /// LSH bands, which raise the threshold (see
+    /// [`LSHBloom::bands_for_capacity`]).
+    pub similarity_threshold: f64,
+    /// Worker threads used by batch operations.
+    pub threads: usize,
+    /// Seed shared by every hash function the engine uses.
+    ///
+    /// Engines that must agree on fingerprints (e.g. crawler workers
+    /// sharing persisted filters) need the same seed.
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            capacity: 1_000_000,
+            false_positive_rate: 0.01,
+            filter: FilterKind::Bloom,
+            verification: None,
+            normalizer: NormalizerConfig::default(),
+            simhash_enabled: true,
+            similarity_threshold: 0.9,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+impl Config {
+    /// Set the expected number of unique URLs.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Forget URLs after a wall-clock window, split into `generations`.
+    ///
+    /// Shorthand for [`FilterKind::Windowed`] with
+    /// [`Rotation::Interval`]`(window / generations)`.
+    pub fn with_time_window(mut self, window: Duration, generations: usize) -> Self {
+        let span = window / generations.max(1) as u32;
+        self.filter = FilterKind::Windowed {
+            generations,
+            rotation: Rotation::Interval(span),
+        };
+        self
+    }
+
+    /// Forget URLs after `inserts` new URLs, split into `generations`.
+    ///
+    /// Shorthand for [`FilterKind::Windowed`] with
+    /// [`Rotation::Inserts`]`(inserts / generations)`.
+    pub fn with_insert_window(mut self, inserts: u64, generations: usize) -> Self {
+        let span = inserts.div_ceil(generations.max(1) as u64);
+        self.filter = FilterKind::Windowed {
+            generations,
+            rotation: Rotation::Inserts(span),
+        };
+        self
+    }
+
+    /// Set the desired Bloom filter false-positive rate.
+    pub fn with_false_positive_rate(mut self, fp_rate: f64) -> Self {
+        self.false_positive_rate = fp_rate;
+        self
+    }
+
+    /// Set the membership filter used for exact-match deduplication.
+    pub fn with_filter(mut self, filter: FilterKind) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Confirm every filter positive against an exact set of
+    /// fingerprints of the given width.
+    ///
+    /// No new URL is then lost to a filter false positive, at the cost
+    /// of `width / 8` bytes (plus hash-table overhead) per URL.
+    /// Only [`DeduplicationEngine`] supports verification, and not with
+    /// [`FilterKind::Windowed`]; the concurrent and sharded engines
+    /// reject it.
+    pub fn with_exact_verification(mut self, width: FingerprintWidth) -> Self {
+        self.verification = Some(width);
+        self
+    }
+
+    /// Set the URL normalization flags.
+    pub fn with_normalizer(mut self, normalizer: NormalizerConfig) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Enable SimHash near-duplicate detection.
+    pub fn enable_simhash(mut self) -> Self {
+        self.simhash_enabled = true;
+        self
+    }
+
+    /// Disable SimHash near-duplicate detection.
+    pub fn disable_simhash(mut self) -> Self {
+        self.simhash_enabled = false;
+        self
+    }
+
+    /// Set the near-duplicate similarity threshold.
+    pub fn with_similarity_threshold(mut self, threshold: f64) -> Self {
+        self.similarity_threshold = threshold;
+        self
+    }
+
+    /// Set the number of worker threads.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Set the hash seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Check that every setting is within its valid range.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.capacity == 0 {
+            return Err(ConfigError::ZeroCapacity);
+        }
+        if !(self.false_positive_rate > 0.0 && self.false_positive_rate < 1.0) {
+            return Err(ConfigError::InvalidFalsePositiveRate(
+                self.false_positive_rate,
+            ));
+        }
+        if !(self.similarity_threshold > 0.0 && self.similarity_threshold <= 1.0) {
+            return Err(ConfigError::InvalidSimilarityThreshold(
+                self.similarity_threshold,
+            ));
+        }
+        if self.threads == 0 {
+            return Err(ConfigError::ZeroThreads);
+        }
+        if let FilterKind::Windowed {
+            generations,
+            rotation,
+        } = self.filter
+            && (generations == 0
+                || rotation == Rotation::Inserts(0)
+                || rotation == Rotation::Interval(Duration::ZERO))
+        {
+            return Err(ConfigError::InvalidWindow);
+        }
+        // Fingerprints never expire, so they would outlive the window
+        // and confirm filter false positives on expired URLs
+        if matches!(self.filter, FilterKind::Windowed { .. }) && self.verification.is_some() {
+            return Err(ConfigError::UnsupportedVerification);
+        }
+        Ok(())
+    }
+}
+
+/// Reasons a [`Config`] can be rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    /// `capacity` must be at least 1.
+    ZeroCapacity,
+    /// `false_positive_rate` must be in `(0.0, 1.0)`.
+    InvalidFalsePositiveRate(f64),
+    /// `similarity_threshold` must be in `(0.0, 1.0]`.
+    InvalidSimilarityThreshold(f64),
+    /// `threads` must be at least 1.
+    ZeroThreads,
+    /// A sharded engine needs at least one shard.
+    ZeroShards,
+    /// The engine does not support the requested [`FilterKind`].
+    UnsupportedFilter(FilterKind),
+    /// A windowed filter needs at least one generation of non-zero span.
+    InvalidWindow,
+    /// The engine or filter does not support exact verification
+    /// ([`Config::with_exact_verification`]).
+    UnsupportedVerification,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ZeroCapacity => write!(f, "capacity must be at least 1"),
+            ConfigError::InvalidFalsePositiveRate(p) => {
+                write!(f, "false positive rate {} is not in (0, 1)", p)
+            }
+            ConfigError::InvalidSimilarityThreshold(t) => {
+                write!(f, "similarity threshold {} is not in (0, 1]", t)
+            }
+            ConfigError::ZeroThreads => write!(f, "thread count must be at least 1"),
+            ConfigError::ZeroShards => write!(f, "shard count must be at least 1"),
+            ConfigError::UnsupportedFilter(kind) => {
+                write!(f, "filter {:?} is not supported by this engine", kind)
+            }
+            ConfigError::InvalidWindow => {
+                write!(f, "window needs at least one generation of non-zero span")
+            }
+            ConfigError::UnsupportedVerification => {
+                write!(
+                    f,
+                    "exact verification is not supported by this engine or filter"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Errors returned by engine operations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EngineError {
+    /// The input could not be parsed as a URL.
+    InvalidUrl(url::ParseError),
+    /// The configured filter cannot remove items.
+    DeletionUnsupported,
+    /// The configured filter has no room for another item.
+    FilterFull,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::InvalidUrl(err) => write!(f, "invalid URL: {}", err),
+            EngineError::DeletionUnsupported => {
+                write!(f, "the configured filter does not support deletion")
+            }
+            EngineError::FilterFull => write!(f, "the configured filter is full"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::InvalidUrl(err) => Some(err),
+            EngineError::DeletionUnsupported | EngineError::FilterFull => None,
+        }
+    }
+}
+
+impl From<url::ParseError> for EngineError {
+    fn from(err: url::ParseError) -> Self {
+        EngineError::InvalidUrl(err)
+    }
+}
+
+impl From<FilterFull> for EngineError {
+    fn from(_: FilterFull) -> Self {
+        EngineError::FilterFull
+    }
+}
+
+/// Deduplication engine combining normalization and membership filtering.
+pub struct DeduplicationEngine {
+    filter: Box<dyn MembershipFilter>,
+    verifier: Option<Box<dyn FingerprintStore>>,
+    normalizer: UrlNormalizer,
+    near_duplicates: Option<NearDuplicateIndex>,
+    stats: Stats,
+    pool: OnceLock<rayon::ThreadPool>,
+    config: Config,
+}
+
+/// Smallest batch whose normalization is spread over worker threads.
+const PARALLEL_BATCH_MIN: usize = 1024;
+
+/// Build the membership filter selected by [`FilterKind`].
+fn build_filter(config: &Config) -> Box<dyn MembershipFilter> {
+    let (capacity, fp_rate, seed) = (config.capacity, config.false_positive_rate, config.seed);
+
+    match config.filter {
+        FilterKind::Bloom => Box::new(BloomFilter::with_seed(capacity, fp_rate, seed)),
+        FilterKind::Scalable => Box::new(ScalableBloomFilter::with_params(
+            capacity,
+            fp_rate,
+            crate::scalable::DEFAULT_GROWTH_FACTOR,
+            crate::scalable::DEFAULT_TIGHTENING_RATIO,
+            seed,
+        )),
+        FilterKind::Counting => Box::new(CountingBloomFilter::with_seed(capacity, fp_rate, seed)),
+        FilterKind::Cuckoo => Box::new(CuckooFilter::with_seed(capacity, seed)),
+        FilterKind::Windowed {
+            generations,
+            rotation,
+        } => Box::new(WindowedBloomFilter::with_seed(
+            capacity,
+            fp_rate,
+            generations,
+            rotation,
+            seed,
+        )),
+    }
+}
+
+/// SimHash engine paired with the LSHBloom index it feeds.
+struct NearDuplicateIndex {
+    simhash: SimHashEngine,
+    lsh: LSHBloom,
+}
+
+/// Internal statistics for observability and testing.
+#[derive(Default)]
+pub(crate) struct Stats {
+    pub(crate) total_checked: AtomicU64,
+    pub(crate) duplicates_found: AtomicU64,
+    pub(crate) urls_inserted: AtomicU64,
+    pub(crate) urls_forgotten: AtomicU64,
+    pub(crate) false_positives_rejected: AtomicU64,
+}
+
+impl Stats {
+    pub(crate) fn snapshot(&self) -> EngineStatsSnapshot {
+        EngineStatsSnapshot {
+            total_checked: self.total_checked.load(Ordering::Relaxed),
+            duplicates_found: self.duplicates_found.load(Ordering::Relaxed),
+            urls_inserted: self.urls_inserted.load(Ordering::Relaxed),
+            urls_forgotten: self.urls_forgotten.load(Ordering::Relaxed),
+            false_positives_rejected: self.false_positives_rejected.load(Ordering::Relaxed),
+        }
+    }
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.total_checked,
+            &self.duplicates_found,
+            &self.urls_inserted,
+            &self.urls_forgotten,
+            &self.false_positives_rejected,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl DeduplicationEngine {
+    /// Create a new deduplication engine from a validated configuration.
+    pub fn new(config: Config) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        let filter = build_filter(&config);
+        Ok(Self::from_parts(config, filter))
+    }
+
+    /// Create a deduplication engine backed by a caller-supplied filter.
+    ///
+    /// `config.filter` is ignored; every other setting applies. The
+    /// filter should be empty and sized for `config.capacity`.
+    pub fn with_filter<F>(config: Config, filter: F) -> Result<Self, ConfigError>
+    where
+        F: MembershipFilter + 'static,
+    {
+        config.validate()?;
+
+        Ok(Self::from_parts(config, Box::new(filter)))
+    }
+
+    /// Confirm filter positives against a caller-supplied fingerprint
+    /// store, e.g. one kept on disk.
+    ///
+    /// Replaces the store selected by [`Config::verification`]. The
+    /// store should be empty.
+    ///
+    /// # Panics
+    /// With [`FilterKind::Windowed`], whose URLs expire while their
+    /// fingerprints would not.
+    pub fn with_fingerprint_store<S>(mut self, store: S) -> Self
+    where
+        S: FingerprintStore + 'static,
+    {
+        assert!(
+            !matches!(self.config.filter, FilterKind::Windowed { .. }),
+            "Windowed filters do not support exact verification"
+        );
+        self.verifier = Some(Box::new(store));
+        self
+    }
+
+    /// Normalize, check, and insert a URL.
+    ///
+    /// With exact verification enabled, a filter positive is only a
+    /// duplicate if the fingerprint store confirms it; otherwise it is
+    /// counted in [`EngineStatsSnapshot::false_positives_rejected`] and
+    /// the URL is recorded as new.
+    ///
+    /// New URLs are also indexed for near-duplicate detection when
+    /// SimHash is enabled.
+    ///
+    /// # Returns
+    /// - `Ok(false)` → URL is new
+    /// - `Ok(true)` → URL is a duplicate
+    /// - `Err(EngineError::FilterFull)` → URL is new but the filter
+    ///   has no room to record it (e.g. a full [`FilterKind::Cuckoo`])
+    pub fn check_and_insert(&mut self, url: &str) -> Result<bool, EngineError> {
+        self.stats.total_checked.fetch_add(1, Ordering::Relaxed);
+
+        let normalized = self.normalizer.normalize(url)?;
+
+        let seen = self.filter.check_and_insert(&normalized);
+        self.record(&normalized, seen)
+    }
+
+    /// [`check_and_insert`](Self::check_and_insert) a batch of URLs.
+    ///
+    /// Normalizes the batch on [`Config::threads`] worker threads, then
+    /// checks it against the filter in one pass so the filter can
+    /// overlap its memory accesses (see [`BloomFilter::insert_batch`]).
+    /// URLs are recorded in order, so a URL repeated within the batch
+    /// is a duplicate from its second occurrence on.
+    ///
+    /// # Returns
+    /// One [`check_and_insert`](Self::check_and_insert) result per URL.
+    pub fn check_and_insert_batch(&mut self, urls: &[&str]) -> Vec<Result<bool, EngineError>> {
+        self.stats
+            .total_checked
+            .fetch_add(urls.len() as u64, Ordering::Relaxed);
+
+        let normalized = self.normalize_batch(urls);
+        let valid: Vec<&str> = normalized
+            .iter()
+            .filter_map(|n| n.as_deref().ok())
+            .collect();
+        let mut seen = self.filter.check_and_insert_batch(&valid).into_iter();
+
+        normalized
+            .iter()
+            .map(|n| match n {
+                Ok(normalized) => {
+                    let seen = seen.next().expect("one filter result per valid URL");
+                    self.record(normalized, seen)
+                }
+                Err(err) => Err(EngineError::InvalidUrl(*err)),
+            })
+            .collect()
+    }
+
+    /// Check whether a URL is a duplicate without inserting it.
+    pub fn is_duplicate(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+        Ok(self.filter.contains(&normalized)
+            && self
+                .verifier
+                .as_ref()
+                .is_none_or(|store| store.contains(fingerprint(&normalized, self.config.seed))))
+    }
+
+    /// Remove a URL from the seen set so it is reported as new again.
+    ///
+    /// Requires a filter that supports removal, such as
+    /// [`FilterKind::Counting`] or [`FilterKind::Cuckoo`]. The URL stays
+    /// in the near-duplicate index.
+    ///
+    /// With exact verification enabled, URLs the fingerprint store has
+    /// never seen are reported as `Ok(false)` without touching the
+    /// filter, so forgetting a false positive cannot evict another URL.
+    /// Filters without removal return
+    /// [`EngineError::DeletionUnsupported`] for every URL.
+    ///
+    /// # Returns
+    /// - `Ok(true)` → URL was seen and has been forgotten
+    /// - `Ok(false)` → URL was never seen
+    pub fn forget(&mut self, url: &str) -> Result<bool, EngineError> {
+        let normalized = self.normalizer.normalize(url)?;
+        if !self.filter.supports_removal() {
+            return Err(EngineError::DeletionUnsupported);
+        }
+
+        // Never seen: leave the filter alone rather than deleting the
+        // entry of whichever URL caused the false positive
+        if let Some(store) = &mut self.verifier
+            && !store.contains(fingerprint(&normalized, self.config.seed))
+        {
+            return Ok(false);
+        }
+
+        let removed = self
+            .filter
+            .remove(&normalized)
+            .ok_or(EngineError::DeletionUnsupported)?;
+        if removed {
+            if let Some(store) = &mut self.verifier {
+                store.remove(fingerprint(&normalized, self.config.seed));
+            }
+            self.stats.urls_forgotten.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(removed)
+    }
+
+    /// Check whether a URL is similar to a previously inserted URL.
+    ///
+    /// Always `Ok(false)` when SimHash is disabled, or when a domain
+    /// rule normalizes the URL into something that is not a URL.
+    pub fn is_near_duplicate(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+
+        Ok(match &self.near_duplicates {
+            Some(index) => index
+                .simhash
+                .try_compute_hash_from_url(&normalized)
+                .is_ok_and(|hash| index.lsh.query_similar(hash)),
+            None => false,
+        })
+    }
+
+    /// Normalize a URL with the engine's normalizer.
+    pub fn normalize(&self, url: &str) -> Result<String, url::ParseError> {
+        self.normalizer.normalize(url)
+    }
+
+    /// Estimated current false-positive rate of the membership filter.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.filter.fp_rate()
+    }
+
+    /// Memory used by the membership filter's table, in bytes.
+    pub fn filter_memory_bytes(&self) -> usize {
+        self.filter.memory_bytes()
+    }
+
+    /// Memory used by the fingerprint store, in bytes; zero without
+    /// exact verification.
+    pub fn fingerprint_memory_bytes(&self) -> usize {
+        self.verifier
+            .as_ref()
+            .map_or(0, |store| store.memory_bytes())
+    }
+
+    /// Configuration the engine was built from.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Access internal statistics (read-only).
+    pub fn stats(&self) -> EngineStatsSnapshot {
+        self.stats.snapshot()
+    }
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Confirm a filter answer for a normalized URL and update the
+    /// stats and near-duplicate index.
+    fn record(
+        &mut self,
+        normalized: &str,
+        seen: Result<bool, FilterFull>,
+    ) -> Result<bool, EngineError> {
+        let mut duplicate = seen?;
+        if let Some(store) = &mut self.verifier {
+            let fp = fingerprint(normalized, self.config.seed);
+            if duplicate && !store.contains(fp) {
+                // Record it in the filter too, so deletion-capable
+                // filters hold one entry per URL
+                self.filter.insert(normalized)?;
+                self.stats
+                    .false_positives_rejected
+                    .fetch_add(1, Ordering::Relaxed);
+                duplicate = false;
+            }
+            store.insert(fp);
+        }
+
+        if duplicate {
+            self.stats.duplicates_found.fetch_add(1, Ordering::Relaxed);
+            Ok(true)
+        } else {
+            if let Some(index) = &mut self.near_duplicates
+                && let Ok(hash) = index.simhash.try_compute_hash_from_url(normalized)
+            {
+                index.lsh.insert(hash);
+            }
+            self.stats.urls_inserted.fetch_add(1, Ordering::Relaxed);
+            Ok(false)
+        }
+    }
+
+    /// Normalize a batch, in parallel when it is large enough to pay
+    /// for the hand-off.
+    fn normalize_batch(&self, urls: &[&str]) -> Vec<Result<String, url::ParseError>> {
+        if self.config.threads == 1 || urls.len() < PARALLEL_BATCH_MIN {
+            return urls
+                .iter()
+                .map(|url| self.normalizer.normalize(url))
+                .collect();
+        }
+
+        let pool = self.pool.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(self.config.threads)
+                .build()
+                .expect("failed to spawn batch worker threads")
+        });
+        pool.install(|| {
+            urls.par_iter()
+                .map(|url| self.normalizer.normalize(url))
+                .collect()
+        })
+    }
+
+    fn from_parts(config: Config, filter: Box<dyn MembershipFilter>) -> Self {
+        let near_duplicates = config.simhash_enabled.then(|| NearDuplicateIndex {
+            simhash: SimHashEngine::with_seed(64, config.seed),
+            lsh: LSHBloom::with_seed(
+                config.capacity,
+                config.false_positive_rate,
+                LSHBloom::bands_for_capacity(
+                    config.similarity_threshold,
+                    config.capacity,
+                    config.false_positive_rate,
+                ),
+                config.seed,
+            ),
+        });
+
+        DeduplicationEngine {
+            filter,
+            verifier: config.verification.map(|width| {
+                Box::new(MemoryFingerprintStore::new(width)) as Box<dyn FingerprintStore>
+            }),
+            normalizer: UrlNormalizer::with_config(config.normalizer.clone()),
+            near_duplicates,
+            stats: Stats::default(),
+            pool: OnceLock::new(),
+            config,
+        }
+    }
+}
+
+/// Immutable snapshot of engine statistics.
+pub struct EngineStatsSnapshot {
+    pub total_checked: u64,
+    pub duplicates_found: u64,
+    pub urls_inserted: u64,
+    pub urls_forgotten: u64,
+    /// Filter positives that the fingerprint store showed to be new URLs.
+    pub false_positives_rejected: u64,
+}
@@ -0,0 +1,306 @@
+//! Common interface of the membership filters.
+//!
+//! [`DeduplicationEngine`](crate::DeduplicationEngine) only needs to ask
+//! "have I seen this string?" and record the answer. [`MembershipFilter`]
+//! captures that contract so the engine can hold any backend: the
+//! filters in this crate, or a caller's own store passed to
+//! [`DeduplicationEngine::with_filter`](crate::DeduplicationEngine::with_filter).
+//! Its lookup half, [`MembershipQuery`], is shared with read-only stores
+//! that cannot record anything.
+
+use std::fmt;
+
+use crate::atomic::AtomicBloomFilter;
+use crate::blocked::BlockedBloomFilter;
+use crate::bloom::BloomFilter;
+use crate::counting::CountingBloomFilter;
+use crate::cuckoo::CuckooFilter;
+use crate::scalable::ScalableBloomFilter;
+use crate::windowed::WindowedBloomFilter;
+
+/// Read-only view of an approximate set of strings with no false
+/// negatives.
+///
+/// Implementations must never report an inserted (and not removed)
+/// item as absent; they may report absent items as present with a
+/// probability of about [`fp_rate`](Self::fp_rate). Read-only stores
+/// such as [`FrozenUrlSet`](crate::frozen::FrozenUrlSet) and
+/// [`MmapBloomFilter`](crate::mmap::MmapBloomFilter) implement only
+/// this trait.
+pub trait MembershipQuery: Send + Sync {
+    /// Whether an item is possibly present.
+    fn contains(&self, item: &str) -> bool;
+
+    /// Approximate number of items held.
+    fn len_estimate(&self) -> u64;
+
+    /// Estimated current false positive rate.
+    fn fp_rate(&self) -> f64;
+
+    /// Heap memory used by the filter's table, in bytes.
+    fn memory_bytes(&self) -> usize;
+}
+
+/// Approximate set of strings that can record new items.
+///
+/// The engine holds one of these; see [`MembershipQuery`] for the
+/// lookup contract.
+pub trait MembershipFilter: MembershipQuery {
+    /// Record an item.
+    ///
+    /// Returns [`FilterFull`] if the filter cannot take another item;
+    /// the filter is then unchanged.
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull>;
+
+    /// Check for an item and record it if absent.
+    ///
+    /// # Returns
+    /// - `Ok(true)` → item was possibly present; nothing was recorded
+    /// - `Ok(false)` → item was absent and has been recorded
+    fn check_and_insert(&mut self, item: &str) -> Result<bool, FilterFull> {
+        if self.contains(item) {
+            Ok(true)
+        } else {
+            self.insert(item).map(|()| false)
+        }
+    }
+
+    /// [`check_and_insert`](Self::check_and_insert) each item in order.
+    ///
+    /// Filters override this to overlap the memory accesses of a batch.
+    fn check_and_insert_batch(&mut self, items: &[&str]) -> Vec<Result<bool, FilterFull>> {
+        items
+            .iter()
+            .map(|item| self.check_and_insert(item))
+            .collect()
+    }
+
+    /// Whether [`remove`](Self::remove) is supported.
+    ///
+    /// Filters that override `remove` must also override this.
+    fn supports_removal(&self) -> bool {
+        false
+    }
+
+    /// Remove an item, or `None` if the filter cannot delete.
+    fn remove(&mut self, _item: &str) -> Option<bool> {
+        None
+    }
+}
+
+/// Error returned when a filter has no room for another item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FilterFull;
+
+impl fmt::Display for FilterFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "filter is full")
+    }
+}
+
+impl std::error::Error for FilterFull {}
+
+impl MembershipQuery for BloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        BloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_bits().div_ceil(64) * 8
+    }
+}
+
+impl MembershipFilter for BloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        BloomFilter::insert(self, item);
+        Ok(())
+    }
+
+    fn check_and_insert_batch(&mut self, items: &[&str]) -> Vec<Result<bool, FilterFull>> {
+        BloomFilter::check_and_insert_batch(self, items)
+            .into_iter()
+            .map(Ok)
+            .collect()
+    }
+}
+
+impl MembershipQuery for BlockedBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        BlockedBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_bits() / 8
+    }
+}
+
+impl MembershipFilter for BlockedBloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        BlockedBloomFilter::insert(self, item);
+        Ok(())
+    }
+}
+
+impl MembershipQuery for AtomicBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        AtomicBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_bits().div_ceil(64) * 8
+    }
+}
+
+impl MembershipFilter for AtomicBloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        AtomicBloomFilter::insert(self, item);
+        Ok(())
+    }
+}
+
+impl MembershipQuery for ScalableBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        ScalableBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_bits().div_ceil(8)
+    }
+}
+
+impl MembershipFilter for ScalableBloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        ScalableBloomFilter::insert(self, item);
+        Ok(())
+    }
+}
+
+impl MembershipQuery for CountingBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        CountingBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_counters().div_ceil(2)
+    }
+}
+
+impl MembershipFilter for CountingBloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        CountingBloomFilter::insert(self, item);
+        Ok(())
+    }
+
+    fn supports_removal(&self) -> bool {
+        true
+    }
+
+    fn remove(&mut self, item: &str) -> Option<bool> {
+        Some(CountingBloomFilter::remove(self, item))
+    }
+}
+
+impl MembershipQuery for CuckooFilter {
+    fn contains(&self, item: &str) -> bool {
+        CuckooFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.len()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_slots() * 2
+    }
+}
+
+impl MembershipFilter for CuckooFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        if CuckooFilter::insert(self, item) {
+            Ok(())
+        } else {
+            Err(FilterFull)
+        }
+    }
+
+    fn supports_removal(&self) -> bool {
+        true
+    }
+
+    fn remove(&mut self, item: &str) -> Option<bool> {
+        Some(CuckooFilter::remove(self, item))
+    }
+}
+
+impl MembershipQuery for WindowedBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        WindowedBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.num_bits().div_ceil(8)
+    }
+}
+
+impl MembershipFilter for WindowedBloomFilter {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        WindowedBloomFilter::insert(self, item);
+        Ok(())
+    }
+
+    fn check_and_insert(&mut self, item: &str) -> Result<bool, FilterFull> {
+        Ok(WindowedBloomFilter::check_and_insert(self, item))
+    }
+}
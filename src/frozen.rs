@@ -0,0 +1,455 @@
+//! Static URL set backed by a binary fuse filter.
+//!
+//! Frozen "already crawled" lists shipped to edge fetchers never change
+//! after they are built, so they do not need a Bloom filter's ability
+//! to insert. A binary fuse filter (Graf & Lemire, 2022) stores an
+//! 8-bit fingerprint table with 1.125 to 1.2 slots per key and answers
+//! a lookup with exactly three probes:
+//!
+//! `fingerprint(x) == F[h0(x)] ^ F[h1(x)] ^ F[h2(x)]`
+//!
+//! For tens of millions of keys that is about 9 bits per key for a
+//! false positive rate of 1/256 (~0.4%), against 9.6 bits per key for a
+//! Bloom filter at 1%.
+//!
+//! URLs are normalized with a [`UrlNormalizer`] before they are hashed,
+//! and the set serializes to a compact, checksummed binary format.
+
+use std::io::{self, Read, Write};
+
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::filter::MembershipQuery;
+use crate::hash::{DEFAULT_SEED, hash_bytes};
+use crate::normalizer::UrlNormalizer;
+use crate::persist::{HeaderReader, PersistError, read_bytes};
+
+/// Magic tag identifying a serialized frozen URL set.
+const MAGIC: [u8; 4] = *b"KFUS";
+
+/// Current on-disk format version.
+const FORMAT_VERSION: u16 = 1;
+
+/// Size of the serialized header in bytes.
+const HEADER_LEN: usize = 56;
+
+/// Offset of the checksum field; the checksum covers everything before it.
+const CHECKSUM_OFFSET: usize = 48;
+
+/// Construction attempts before giving up (each with a fresh seed).
+const MAX_ATTEMPTS: usize = 100;
+
+/// Largest segment length used by the construction.
+const MAX_SEGMENT_LENGTH: u32 = 1 << 18;
+
+/// Read-only URL set with ~0.4% false positives and 3 probes per lookup.
+///
+/// # Characteristics
+/// - No false negatives for URLs in the build list
+/// - False positive rate 1/256, independent of size
+/// - 9 to 10 bits per URL; cannot be modified after it is built
+///
+/// # Fields
+/// - `fingerprints`: Fingerprint table (F)
+/// - `seed`: Seed of the stable URL hash
+/// - `mix_seed`: Seed under which construction succeeded
+/// - `segment_length`: Length of one segment of the table
+/// - `segment_count_length`: Segment length times segment count
+/// - `len`: Number of distinct URLs stored
+/// - `normalizer`: Normalizer applied by [`contains_url`](Self::contains_url)
+pub struct FrozenUrlSet {
+    fingerprints: Vec<u8>,
+    seed: u64,
+    mix_seed: u64,
+    segment_length: u32,
+    segment_count_length: u32,
+    len: u64,
+    normalizer: UrlNormalizer,
+}
+
+impl FrozenUrlSet {
+    /// Build a set from raw URLs using the default normalizer and
+    /// [`DEFAULT_SEED`].
+    ///
+    /// URLs that fail to normalize are skipped; duplicates (before or
+    /// after normalization) are stored once.
+    pub fn build<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::build_with(urls, UrlNormalizer::new(), DEFAULT_SEED)
+    }
+
+    /// Build a set with an explicit normalizer and hash seed.
+    ///
+    /// The seed must match the one used by any
+    /// [`DeduplicationEngine`](crate::DeduplicationEngine) the set is
+    /// plugged into.
+    pub fn build_with<I, S>(urls: I, normalizer: UrlNormalizer, seed: u64) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let keys: Vec<u64> = urls
+            .into_iter()
+            .filter_map(|url| normalizer.normalize(url.as_ref()).ok())
+            .map(|normalized| hash_bytes(normalized.as_bytes(), seed))
+            .collect();
+
+        let mut set = Self::construct(&keys, seed);
+        set.normalizer = normalizer;
+        set
+    }
+
+    /// Replace the normalizer used by [`contains_url`](Self::contains_url).
+    ///
+    /// Loaded sets start with the default normalizer; supply the one
+    /// the set was built with if it had custom rules.
+    pub fn with_normalizer(mut self, normalizer: UrlNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Check whether an already-normalized URL is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the URL is **definitely not present**
+    /// - `true` if the URL is **possibly present**
+    pub fn contains(&self, normalized: &str) -> bool {
+        if self.fingerprints.is_empty() {
+            return false;
+        }
+
+        let hash = mix(hash_bytes(normalized.as_bytes(), self.seed), self.mix_seed);
+        let [h0, h1, h2] = self.positions(hash);
+
+        fingerprint(hash) == self.fingerprints[h0] ^ self.fingerprints[h1] ^ self.fingerprints[h2]
+    }
+
+    /// Normalize a URL and check whether it is possibly in the set.
+    pub fn contains_url(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+        Ok(self.contains(&normalized))
+    }
+
+    /// Number of distinct URLs stored.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the set holds no URLs.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size of the fingerprint table, in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    /// Table bits spent per stored URL.
+    pub fn bits_per_key(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+        self.fingerprints.len() as f64 * 8.0 / self.len as f64
+    }
+
+    /// Theoretical false positive rate (`2^-8`).
+    pub fn false_positive_rate(&self) -> f64 {
+        1.0 / 256.0
+    }
+
+    /// Seed of the stable URL hash.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Write the set in a versioned, checksummed binary format.
+    ///
+    /// The normalizer is not stored.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut header = self.header();
+        let checksum = self.checksum(&header);
+        header[CHECKSUM_OFFSET..].copy_from_slice(&checksum.to_le_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&self.fingerprints)?;
+        writer.flush()
+    }
+
+    /// Read a set written by [`save`](Self::save).
+    ///
+    /// Rejects files with a wrong magic tag, an unsupported version, an
+    /// inconsistent header or a checksum mismatch.
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, PersistError> {
+        let mut raw = [0u8; HEADER_LEN];
+        reader.read_exact(&mut raw)?;
+        let mut r = HeaderReader::new(&raw);
+
+        if r.bytes::<4>() != MAGIC {
+            return Err(PersistError::BadMagic);
+        }
+        let version = r.u16();
+        if version != FORMAT_VERSION {
+            return Err(PersistError::UnsupportedVersion(version));
+        }
+        r.u16();
+
+        let seed = r.u64();
+        let mix_seed = r.u64();
+        let segment_length = r.u32();
+        let segment_count_length = r.u32();
+        let array_length = r.u64();
+        let len = r.u64();
+        let checksum = r.u64();
+
+        // Lookups index the table with these fields, so a layout that
+        // passes the checksum must still be one `with_layout` can produce
+        let segments_fit = segment_length.is_power_of_two()
+            && segment_count_length >= segment_length
+            && segment_count_length.is_multiple_of(segment_length)
+            && array_length == segment_count_length as u64 + 2 * segment_length as u64;
+        if array_length != 0 && !segments_fit || len > array_length {
+            return Err(PersistError::Corrupt("inconsistent table layout"));
+        }
+
+        let set = Self {
+            fingerprints: read_bytes(&mut reader, array_length)?,
+            seed,
+            mix_seed,
+            segment_length,
+            segment_count_length,
+            len,
+            normalizer: UrlNormalizer::new(),
+        };
+        if set.checksum(&set.header()) != checksum {
+            return Err(PersistError::ChecksumMismatch);
+        }
+        Ok(set)
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Build the fingerprint table for a list of 64-bit keys.
+    ///
+    /// Follows the reference construction: hash every key into three
+    /// overlapping segments, peel slots that hold a single key, then
+    /// assign fingerprints in reverse peeling order.
+    fn construct(keys: &[u64], seed: u64) -> Self {
+        let mut set = Self::with_layout(keys.len(), seed);
+        if keys.is_empty() {
+            return set;
+        }
+
+        let capacity = set.fingerprints.len();
+        let mut t2count = vec![0u8; capacity];
+        let mut t2hash = vec![0u64; capacity];
+        let mut alone = Vec::with_capacity(capacity);
+        let mut stack: Vec<(u64, u8)> = Vec::with_capacity(keys.len());
+        let mut rng = seed;
+
+        for attempt in 0.. {
+            assert!(
+                attempt < MAX_ATTEMPTS,
+                "Binary fuse construction failed after {} attempts",
+                MAX_ATTEMPTS
+            );
+
+            set.mix_seed = splitmix64(&mut rng);
+            t2count.fill(0);
+            t2hash.fill(0);
+            stack.clear();
+
+            let mut overflow = false;
+            let mut duplicates = 0;
+            for &key in keys {
+                let hash = mix(key, set.mix_seed);
+                let [h0, h1, h2] = set.positions(hash);
+
+                for (slot, index) in [h0, h1, h2].into_iter().zip(0u8..) {
+                    t2count[slot] = t2count[slot].wrapping_add(4) ^ index;
+                    t2hash[slot] ^= hash;
+                }
+
+                // Two copies of one key cancel out in t2hash; drop the
+                // second one instead of letting it block peeling
+                if t2hash[h0] & t2hash[h1] & t2hash[h2] == 0
+                    && [h0, h1, h2]
+                        .iter()
+                        .any(|&s| t2hash[s] == 0 && t2count[s] == 8)
+                {
+                    duplicates += 1;
+                    for (slot, index) in [h0, h1, h2].into_iter().zip(0u8..) {
+                        t2count[slot] = t2count[slot].wrapping_sub(4) ^ index;
+                        t2hash[slot] ^= hash;
+                    }
+                }
+
+                overflow |= [h0, h1, h2].iter().any(|&s| t2count[s] < 4);
+            }
+            if overflow {
+                continue;
+            }
+
+            alone.clear();
+            alone.extend((0..capacity).filter(|&i| t2count[i] >> 2 == 1));
+
+            while let Some(index) = alone.pop() {
+                if t2count[index] >> 2 != 1 {
+                    continue;
+                }
+
+                let hash = t2hash[index];
+                let found = t2count[index] & 3;
+                stack.push((hash, found));
+
+                let [h0, h1, h2] = set.positions(hash);
+                let h012 = [h0, h1, h2, h0, h1];
+                for step in 1..=2 {
+                    let other = h012[(found + step) as usize];
+                    t2count[other] = t2count[other].wrapping_sub(4) ^ ((found + step) % 3);
+                    t2hash[other] ^= hash;
+                    if t2count[other] >> 2 == 1 {
+                        alone.push(other);
+                    }
+                }
+            }
+
+            if stack.len() + duplicates == keys.len() {
+                set.len = stack.len() as u64;
+                break;
+            }
+        }
+
+        for &(hash, found) in stack.iter().rev() {
+            let [h0, h1, h2] = set.positions(hash);
+            let h012 = [h0, h1, h2, h0, h1];
+            let found = found as usize;
+
+            set.fingerprints[h012[found]] = fingerprint(hash)
+                ^ set.fingerprints[h012[found + 1]]
+                ^ set.fingerprints[h012[found + 2]];
+        }
+
+        set
+    }
+
+    /// Empty set with the table layout for `size` keys.
+    fn with_layout(size: usize, seed: u64) -> Self {
+        let mut set = Self {
+            fingerprints: Vec::new(),
+            seed,
+            mix_seed: 0,
+            segment_length: 0,
+            segment_count_length: 0,
+            len: 0,
+            normalizer: UrlNormalizer::new(),
+        };
+        if size == 0 {
+            return set;
+        }
+
+        let n = size as f64;
+        let segment_length = if size == 1 {
+            4
+        } else {
+            (1u32 << (n.ln() / 3.33f64.ln() + 2.25).floor() as u32).min(MAX_SEGMENT_LENGTH)
+        };
+        let size_factor = if size == 1 {
+            0.0
+        } else {
+            (0.875 + 0.25 * 1e6f64.ln() / n.ln()).max(1.125)
+        };
+        let capacity = (n * size_factor).round() as u32;
+
+        // Three overlapping windows of `segment_length` over the table;
+        // at least one segment of starting positions
+        let segment_count = capacity.div_ceil(segment_length).saturating_sub(2).max(1);
+
+        set.segment_length = segment_length;
+        set.segment_count_length = segment_count * segment_length;
+        set.fingerprints = vec![0; ((segment_count + 2) * segment_length) as usize];
+        set
+    }
+
+    /// The three table slots of a mixed hash, one per segment window.
+    #[inline]
+    fn positions(&self, hash: u64) -> [usize; 3] {
+        let mask = (self.segment_length - 1) as u64;
+        let h0 = ((hash as u128 * self.segment_count_length as u128) >> 64) as u64;
+        let h1 = (h0 + self.segment_length as u64) ^ ((hash >> 18) & mask);
+        let h2 = (h0 + 2 * self.segment_length as u64) ^ (hash & mask);
+        [h0 as usize, h1 as usize, h2 as usize]
+    }
+
+    /// Header with every field except the checksum filled in.
+    fn header(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[0..4].copy_from_slice(&MAGIC);
+        out[4..6].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out[8..16].copy_from_slice(&self.seed.to_le_bytes());
+        out[16..24].copy_from_slice(&self.mix_seed.to_le_bytes());
+        out[24..28].copy_from_slice(&self.segment_length.to_le_bytes());
+        out[28..32].copy_from_slice(&self.segment_count_length.to_le_bytes());
+        out[32..40].copy_from_slice(&(self.fingerprints.len() as u64).to_le_bytes());
+        out[40..48].copy_from_slice(&self.len.to_le_bytes());
+        out
+    }
+
+    /// Checksum over the header fields preceding the checksum and the table.
+    fn checksum(&self, header: &[u8; HEADER_LEN]) -> u64 {
+        let mut hasher = Xxh3::new();
+        hasher.update(&header[..CHECKSUM_OFFSET]);
+        hasher.update(&self.fingerprints);
+        hasher.digest()
+    }
+}
+
+impl MembershipQuery for FrozenUrlSet {
+    fn contains(&self, item: &str) -> bool {
+        FrozenUrlSet::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.len
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.fingerprints.len()
+    }
+}
+
+/// 8-bit fingerprint of a mixed hash.
+#[inline]
+fn fingerprint(hash: u64) -> u8 {
+    (hash ^ (hash >> 32)) as u8
+}
+
+/// Re-randomize a key hash for one construction attempt (MurmurHash3 finalizer).
+#[inline]
+fn mix(key: u64, seed: u64) -> u64 {
+    let mut h = key.wrapping_add(seed);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^ (h >> 33)
+}
+
+/// SplitMix64 step producing construction seeds.
+#[inline]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
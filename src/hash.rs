@@ -0,0 +1,229 @@
+//! Stable, seedable hashing.
+//!
+//! Every fingerprint produced by this crate is derived from XXH3-64
+//! with an explicit seed. XXH3 output is fixed by its specification, so
+//! two processes (or two crate versions) given the same seed compute
+//! identical hashes, and filters or fingerprints can be shared,
+//! persisted and compared across machines.
+//!
+//! Integers are always fed to the hasher in little-endian byte order so
+//! results do not depend on the host platform. Values hashed through
+//! the standard [`Hash`](std::hash::Hash) trait are only as stable as
+//! their `Hash` impls, which may change between Rust releases; the
+//! [`StableHash`] trait fixes the encoding of strings, byte slices and
+//! tuples as well.
+
+use std::hash::{BuildHasher, Hasher};
+
+use xxhash_rust::xxh3::{Xxh3, xxh3_64_with_seed};
+
+/// Seed used when no explicit seed is supplied.
+pub const DEFAULT_SEED: u64 = 0x6b61_6b61_5eed_0002;
+
+/// Hash a byte slice with XXH3-64 and the given seed.
+#[inline]
+pub fn hash_bytes(bytes: &[u8], seed: u64) -> u64 {
+    xxh3_64_with_seed(bytes, seed)
+}
+
+/// [`BuildHasher`] producing seeded [`StableHasher`]s.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StableState {
+    seed: u64,
+}
+
+impl StableState {
+    /// Create a hasher factory with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Seed used by every hasher built from this state.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for StableState {
+    fn default() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildHasher for StableState {
+    type Hasher = StableHasher;
+
+    fn build_hasher(&self) -> StableHasher {
+        StableHasher::with_seed(self.seed)
+    }
+}
+
+/// Streaming XXH3-64 hasher with platform-independent integer encoding.
+#[derive(Clone)]
+pub struct StableHasher {
+    inner: Xxh3,
+}
+
+impl StableHasher {
+    /// Create a hasher with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            inner: Xxh3::with_seed(seed),
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.digest()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    /// `usize` is widened to 64 bits so 32- and 64-bit hosts agree.
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Hashing with an encoding fixed by this crate.
+///
+/// Unlike [`Hash`](std::hash::Hash), whose impls for `str`, slices and
+/// tuples belong to the standard library, every encoding here is part
+/// of the crate's format:
+/// - Integers: little-endian bytes, `usize` / `isize` widened to 64 bits
+/// - `bool` as one byte, `char` as a `u32`
+/// - `str`: its UTF-8 bytes followed by `0xFF`, a byte UTF-8 never uses
+/// - Slices, arrays and `Vec`s: length as a `u64`, then each element
+/// - Tuples: each field in order
+pub trait StableHash {
+    /// Feed the value into `hasher`.
+    fn stable_hash(&self, hasher: &mut StableHasher);
+}
+
+macro_rules! stable_hash_int {
+    ($($int:ty => $write:ident as $as:ty),* $(,)?) => {
+        $(
+            impl StableHash for $int {
+                #[inline]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    hasher.$write(*self as $as);
+                }
+            }
+        )*
+    };
+}
+
+stable_hash_int! {
+    u8 => write_u8 as u8,
+    u16 => write_u16 as u16,
+    u32 => write_u32 as u32,
+    u64 => write_u64 as u64,
+    u128 => write_u128 as u128,
+    usize => write_u64 as u64,
+    i8 => write_u8 as u8,
+    i16 => write_u16 as u16,
+    i32 => write_u32 as u32,
+    i64 => write_u64 as u64,
+    i128 => write_u128 as u128,
+    isize => write_u64 as u64,
+    bool => write_u8 as u8,
+    char => write_u32 as u32,
+}
+
+impl StableHash for str {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write(self.as_bytes());
+        hasher.write_u8(0xff);
+    }
+}
+
+impl StableHash for String {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.as_str().stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash> StableHash for [T] {
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_u64(self.len() as u64);
+        for item in self {
+            item.stable_hash(hasher);
+        }
+    }
+}
+
+impl<T: StableHash, const N: usize> StableHash for [T; N] {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.as_slice().stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash> StableHash for Vec<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.as_slice().stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash + ?Sized> StableHash for &T {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (**self).stable_hash(hasher);
+    }
+}
+
+macro_rules! stable_hash_tuple {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: StableHash),+> StableHash for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    let ($($name,)+) = self;
+                    $($name.stable_hash(hasher);)+
+                }
+            }
+        )*
+    };
+}
+
+stable_hash_tuple! {
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+}
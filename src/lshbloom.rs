@@ -1 +1,316 @@
+//! LSHBloom index for near-duplicate detection.
+//!
+//! Implements the LSHBloom scheme (arXiv:2411.04257): a 64-bit SimHash
+//! signature is split into `b` bands of `r` rows each, and every band is
+//! stored in its own Bloom filter. A query is reported as a
+//! near-duplicate when **any** band matches a previously inserted band.
+//!
+//! Compared to keeping every `SimHash` in memory, the index costs only a
+//! few bits per band per URL, which makes near-duplicate detection
+//! feasible over billions of URLs.
+//!
+//! A band of `r` rows takes only `2^r` distinct values, so unrelated
+//! signatures also collide inside a band: after `n` inserts a query
+//! matches some band with probability of about `b * n / 2^r`, whatever
+//! the Bloom filters' size. Many narrow bands (a low threshold) are
+//! therefore only usable for small indexes; see
+//! [`LSHBloom::bands_for_capacity`].
+//!
+//! [`AtomicLSHBloom`] is the same index over
+//! [`AtomicBloomFilter`] bands, for inserting from many threads through
+//! `&self`.
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::atomic::AtomicBloomFilter;
+use crate::bloom::BloomFilter;
+use crate::hash::DEFAULT_SEED;
+use crate::simhash::SimHash;
+
+/// Width of the signatures indexed by [`LSHBloom`].
+const SIGNATURE_BITS: u32 = 64;
+
+/// Banded Bloom filter index over SimHash signatures.
+///
+/// # Characteristics
+/// - No false negatives for signatures sharing at least one full band
+/// - Bloom false positives bounded by the configured `fp_rate`
+/// - Band collisions between unrelated signatures at about
+///   `b * n / 2^r`, on top of the Bloom false positives
+/// - Memory independent of signature storage
+///
+/// # Fields
+/// - `bands`: One Bloom filter per band
+/// - `rows_per_band`: Number of signature bits per band (r)
+/// - `items_inserted`: Count of inserted signatures
+pub struct LSHBloom {
+    bands: Vec<BloomFilter>,
+    rows_per_band: u32,
+    items_inserted: u64,
+}
+
+impl LSHBloom {
+    /// Create a new LSHBloom index.
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of signatures (n)
+    /// - `fp_rate`: Desired probability that an unrelated signature is
+    ///   reported as similar because of Bloom collisions
+    /// - `bands`: Number of bands (b); must divide 64, and leave bands
+    ///   of `r = 64 / b` rows with at least `capacity` values (`2^r`)
+    ///
+    /// Each band filter is sized for `fp_rate / bands`, so the union of
+    /// all bands stays within `fp_rate`.
+    pub fn new(capacity: usize, fp_rate: f64, bands: usize) -> Self {
+        Self::with_seed(capacity, fp_rate, bands, DEFAULT_SEED)
+    }
+
+    /// Create a new LSHBloom index whose band filters use `seed`.
+    pub fn with_seed(capacity: usize, fp_rate: f64, bands: usize, seed: u64) -> Self {
+        let rows = band_rows(capacity, bands);
+        let band_fp_rate = fp_rate / bands as f64;
+
+        Self {
+            bands: (0..bands)
+                .map(|_| BloomFilter::with_seed(capacity, band_fp_rate, seed))
+                .collect(),
+            rows_per_band: rows,
+            items_inserted: 0,
+        }
+    }
+
+    /// Pick the band count whose similarity threshold is closest to `threshold`.
+    ///
+    /// The LSH threshold (the similarity at which the candidate
+    /// probability curve is steepest) is approximately `(1/b)^(1/r)`.
+    pub fn bands_for_threshold(threshold: f64) -> usize {
+        Self::closest_bands(threshold, |_| true)
+    }
+
+    /// Pick the band count closest to `threshold` whose bands stay
+    /// selective with `capacity` signatures inserted.
+    ///
+    /// Band counts whose band collisions (`b * n / 2^r`) would exceed
+    /// `fp_rate` are skipped, so large indexes get fewer, wider bands
+    /// and a higher threshold than requested. One band always
+    /// qualifies.
+    pub fn bands_for_capacity(threshold: f64, capacity: usize, fp_rate: f64) -> usize {
+        Self::closest_bands(threshold, |bands| {
+            let rows = SIGNATURE_BITS as i32 / bands as i32;
+            bands as f64 * capacity as f64 <= fp_rate * 2f64.powi(rows)
+        })
+    }
+
+    /// Insert a SimHash signature into every band.
+    pub fn insert(&mut self, hash: SimHash) {
+        let rows = self.rows_per_band;
+        for (i, band) in self.bands.iter_mut().enumerate() {
+            band.insert_hashable(&Self::band_value(hash, i as u32, rows));
+        }
+
+        self.items_inserted += 1;
+    }
+
+    /// Check whether a similar signature has been inserted.
+    ///
+    /// Returns `true` as soon as any band matches.
+    pub fn query_similar(&self, hash: SimHash) -> bool {
+        let rows = self.rows_per_band;
+        self.bands
+            .iter()
+            .enumerate()
+            .any(|(i, band)| band.contains_hashable(&Self::band_value(hash, i as u32, rows)))
+    }
+
+    /// Query and insert a signature in one call.
+    ///
+    /// # Returns
+    /// - `false` → no similar signature was present
+    /// - `true` → a similar signature was present
+    pub fn check_and_insert(&mut self, hash: SimHash) -> bool {
+        let similar = self.query_similar(hash);
+        self.insert(hash);
+        similar
+    }
+
+    /// Count how many bands of `hash` match an inserted signature.
+    pub fn matching_bands(&self, hash: SimHash) -> usize {
+        let rows = self.rows_per_band;
+        self.bands
+            .iter()
+            .enumerate()
+            .filter(|(i, band)| band.contains_hashable(&Self::band_value(hash, *i as u32, rows)))
+            .count()
+    }
+
+    /// Probability that a signature with the given similarity to an
+    /// inserted one becomes a candidate.
+    ///
+    /// Formula:
+    /// `1 - (1 - s^r)^b`
+    pub fn candidate_probability(&self, similarity: f64) -> f64 {
+        let b = self.bands.len() as i32;
+        let r = self.rows_per_band as i32;
+
+        1.0 - (1.0 - similarity.powi(r)).powi(b)
+    }
+
+    /// Approximate similarity threshold of this index: `(1/b)^(1/r)`.
+    pub fn threshold(&self) -> f64 {
+        Self::threshold_for(self.bands.len())
+    }
+
+    /// Number of bands (b).
+    pub fn num_bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Number of signature bits per band (r).
+    pub fn rows_per_band(&self) -> u32 {
+        self.rows_per_band
+    }
+
+    /// Number of signatures inserted so far.
+    pub fn len(&self) -> u64 {
+        self.items_inserted
+    }
+
+    /// Whether no signature has been inserted yet.
+    pub fn is_empty(&self) -> bool {
+        self.items_inserted == 0
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    #[inline]
+    fn band_value(hash: SimHash, band: u32, rows: u32) -> u64 {
+        let mask = if rows == SIGNATURE_BITS {
+            u64::MAX
+        } else {
+            (1u64 << rows) - 1
+        };
+        (hash.0 >> (band * rows)) & mask
+    }
+
+    /// Band count dividing 64 and accepted by `allowed` whose threshold
+    /// is closest to `threshold`.
+    fn closest_bands(threshold: f64, allowed: impl Fn(usize) -> bool) -> usize {
+        (0..=SIGNATURE_BITS.trailing_zeros())
+            .map(|shift| 1usize << shift)
+            .filter(|&bands| bands == 1 || allowed(bands))
+            .min_by(|&a, &b| {
+                let da = (Self::threshold_for(a) - threshold).abs();
+                let db = (Self::threshold_for(b) - threshold).abs();
+                da.total_cmp(&db)
+            })
+            .unwrap_or(1)
+    }
+
+    #[inline]
+    fn threshold_for(bands: usize) -> f64 {
+        let r = SIGNATURE_BITS as f64 / bands as f64;
+        (1.0 / bands as f64).powf(1.0 / r)
+    }
+}
+
+/// [`LSHBloom`] whose operations take `&self`.
+///
+/// # Characteristics
+/// - Same bands, band filters and hashes as [`LSHBloom`], so both
+///   answer queries identically
+/// - `Send + Sync`; inserts set bits with atomic `fetch_or` and take
+///   no locks
+///
+/// # Fields
+/// - `bands`: One atomic Bloom filter per band
+/// - `rows_per_band`: Number of signature bits per band (r)
+/// - `items_inserted`: Count of inserted signatures
+pub struct AtomicLSHBloom {
+    bands: Vec<AtomicBloomFilter>,
+    rows_per_band: u32,
+    items_inserted: AtomicU64,
+}
+
+impl AtomicLSHBloom {
+    /// Create a new atomic LSHBloom index.
+    ///
+    /// Arguments as for [`LSHBloom::new`].
+    pub fn new(capacity: usize, fp_rate: f64, bands: usize) -> Self {
+        Self::with_seed(capacity, fp_rate, bands, DEFAULT_SEED)
+    }
+
+    /// Create a new atomic LSHBloom index whose band filters use `seed`.
+    pub fn with_seed(capacity: usize, fp_rate: f64, bands: usize, seed: u64) -> Self {
+        let rows = band_rows(capacity, bands);
+        let band_fp_rate = fp_rate / bands as f64;
+
+        Self {
+            bands: (0..bands)
+                .map(|_| AtomicBloomFilter::with_seed(capacity, band_fp_rate, seed))
+                .collect(),
+            rows_per_band: rows,
+            items_inserted: AtomicU64::new(0),
+        }
+    }
+
+    /// Insert a SimHash signature into every band.
+    pub fn insert(&self, hash: SimHash) {
+        let rows = self.rows_per_band;
+        for (i, band) in self.bands.iter().enumerate() {
+            band.insert_hashable(&LSHBloom::band_value(hash, i as u32, rows));
+        }
+
+        self.items_inserted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Check whether a similar signature has been inserted.
+    ///
+    /// Returns `true` as soon as any band matches.
+    pub fn query_similar(&self, hash: SimHash) -> bool {
+        let rows = self.rows_per_band;
+        self.bands
+            .iter()
+            .enumerate()
+            .any(|(i, band)| band.contains_hashable(&LSHBloom::band_value(hash, i as u32, rows)))
+    }
+
+    /// Number of bands (b).
+    pub fn num_bands(&self) -> usize {
+        self.bands.len()
+    }
+
+    /// Number of signature bits per band (r).
+    pub fn rows_per_band(&self) -> u32 {
+        self.rows_per_band
+    }
+
+    /// Number of signatures inserted so far.
+    pub fn len(&self) -> u64 {
+        self.items_inserted.load(Ordering::Relaxed)
+    }
+
+    /// Whether no signature has been inserted yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Rows per band for `bands` bands over a signature, checking that the
+/// band count divides it and that a band has `capacity` values.
+fn band_rows(capacity: usize, bands: usize) -> u32 {
+    assert!(
+        bands > 0 && bands as u32 <= SIGNATURE_BITS && SIGNATURE_BITS.is_multiple_of(bands as u32),
+        "Number of bands must divide {}",
+        SIGNATURE_BITS
+    );
+    let rows = SIGNATURE_BITS / bands as u32;
+    assert!(
+        capacity as u128 <= 1u128 << rows,
+        "Capacity exceeds the {} values of a {}-row band",
+        1u128 << rows,
+        rows
+    );
+    rows
+}
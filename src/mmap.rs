@@ -0,0 +1,293 @@
+//! Memory-mapped Bloom filter.
+//!
+//! Maps a file in the [`BloomFilter`](crate::bloom::BloomFilter)
+//! on-disk format directly into memory instead of reading it. Opening
+//! is constant-time regardless of filter size, the OS pages the bit
+//! array in lazily on first access, and several processes on one host
+//! can share a single read-only filter through the page cache.
+//!
+//! The bit layout is identical to the in-memory filter, so a file
+//! written by `BloomFilter::save` can be opened here and a file
+//! flushed from here can be loaded with `BloomFilter::load`.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::bloom::{
+    BloomHeader, HEADER_LEN, cardinality_from_fill, hash_pair, optimal_params, probe_index,
+};
+use crate::filter::MembershipQuery;
+use crate::persist::PersistError;
+
+/// Bloom filter whose bit array lives in a memory-mapped file.
+///
+/// # Fields
+/// - `map`: Mapping of the whole file (header + payload)
+/// - `header`: Decoded header; `items_inserted` is kept current in memory
+pub struct MmapBloomFilter {
+    map: Mapping,
+    header: BloomHeader,
+}
+
+/// Read-only or writable view of the file.
+enum Mapping {
+    ReadOnly(Mmap),
+    ReadWrite(MmapMut),
+}
+
+impl Mapping {
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Mapping::ReadOnly(map) => map,
+            Mapping::ReadWrite(map) => map,
+        }
+    }
+}
+
+impl MmapBloomFilter {
+    /// Map an existing filter file read-only.
+    ///
+    /// Only the header is validated; the checksum is not verified so
+    /// that opening stays instant. Call [`verify`](Self::verify) to
+    /// check the full contents.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistError> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only. Concurrent truncation of the
+        // file by another process is outside the supported usage.
+        let map = unsafe { Mmap::map(&file)? };
+        Self::from_mapping(Mapping::ReadOnly(map))
+    }
+
+    /// Map an existing filter file for reading and writing.
+    ///
+    /// Inserts modify the file through a shared mapping; call
+    /// [`flush`](Self::flush) to update the header and persist changes.
+    pub fn open_mut<P: AsRef<Path>>(path: P) -> Result<Self, PersistError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        // SAFETY: see `open`; writers must not share a file with other
+        // writers.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Self::from_mapping(Mapping::ReadWrite(map))
+    }
+
+    /// Create (or truncate) a file holding an empty filter and map it writable.
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of elements (n)
+    /// - `fp_rate`: Desired false positive probability (p)
+    /// - `seed`: Hash seed, see [`BloomFilter::with_seed`](crate::bloom::BloomFilter::with_seed)
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        capacity: usize,
+        fp_rate: f64,
+        seed: u64,
+    ) -> Result<Self, PersistError> {
+        let (m, k) = optimal_params(capacity, fp_rate);
+        let mut header = BloomHeader {
+            num_bits: m as u64,
+            num_hashes: k,
+            seed,
+            items_inserted: 0,
+            checksum: 0,
+        };
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(HEADER_LEN as u64 + header.num_words() * 8)?;
+
+        // SAFETY: the file was just created with the expected length.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        header.checksum = header.compute_checksum_bytes(&map[HEADER_LEN..]);
+        map[..HEADER_LEN].copy_from_slice(&header.encode());
+
+        Ok(Self {
+            map: Mapping::ReadWrite(map),
+            header,
+        })
+    }
+
+    /// Insert an element into the filter.
+    ///
+    /// Returns an [`io::ErrorKind::PermissionDenied`] error, leaving the
+    /// filter unchanged, if it was opened with [`open`](Self::open).
+    pub fn insert(&mut self, value: &str) -> io::Result<()> {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.header.seed);
+        let m = self.header.num_bits;
+
+        let Mapping::ReadWrite(map) = &mut self.map else {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "filter was opened read-only",
+            ));
+        };
+        let payload = &mut map[HEADER_LEN..];
+        for i in 0..self.header.num_hashes {
+            let index = probe_index(h1, h2, i, m);
+            payload[index >> 3] |= 1 << (index & 7);
+        }
+
+        self.header.items_inserted += 1;
+        Ok(())
+    }
+
+    /// Check whether an element is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        let (h1, h2) = hash_pair(value.as_bytes(), self.header.seed);
+        let m = self.header.num_bits;
+        let payload = self.payload();
+
+        (0..self.header.num_hashes).all(|i| {
+            let index = probe_index(h1, h2, i, m);
+            payload[index >> 3] & (1 << (index & 7)) != 0
+        })
+    }
+
+    /// Write the item count and a fresh checksum into the header and
+    /// flush the mapping to disk.
+    ///
+    /// Recomputing the checksum reads the whole bit array. A no-op for
+    /// read-only filters.
+    pub fn flush(&mut self) -> io::Result<()> {
+        // Leave the stored checksum alone so `verify` still checks the
+        // file against what was written
+        if let Mapping::ReadOnly(_) = self.map {
+            return Ok(());
+        }
+
+        self.header.checksum = self.header.compute_checksum_bytes(self.payload());
+        if let Mapping::ReadWrite(map) = &mut self.map {
+            map[..HEADER_LEN].copy_from_slice(&self.header.encode());
+            map.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Verify the stored checksum against the full file contents.
+    ///
+    /// Reads (and therefore pages in) the whole bit array.
+    pub fn verify(&self) -> Result<(), PersistError> {
+        if self.header.compute_checksum_bytes(self.payload()) == self.header.checksum {
+            Ok(())
+        } else {
+            Err(PersistError::ChecksumMismatch)
+        }
+    }
+
+    /// Whether the filter accepts inserts.
+    pub fn is_writable(&self) -> bool {
+        matches!(self.map, Mapping::ReadWrite(_))
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.header.seed
+    }
+
+    /// Size of the bit array (m).
+    pub fn num_bits(&self) -> usize {
+        self.header.num_bits as usize
+    }
+
+    /// Number of hash functions (k).
+    pub fn num_hashes(&self) -> u32 {
+        self.header.num_hashes
+    }
+
+    /// Number of insert calls recorded so far (n).
+    pub fn items_inserted(&self) -> u64 {
+        self.header.items_inserted
+    }
+
+    /// Estimate the current false positive rate.
+    ///
+    /// Formula:
+    /// `(1 - e^(-k * n / m))^k`
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = self.header.num_hashes as f64;
+        let n = self.header.items_inserted as f64;
+        let m = self.header.num_bits as f64;
+
+        (1.0 - (-k * n / m).exp()).powf(k)
+    }
+
+    /// Fraction of bits currently set (X / m).
+    ///
+    /// Reads (and therefore pages in) the whole bit array.
+    pub fn fill_ratio(&self) -> f64 {
+        let ones: u64 = self.payload().iter().map(|b| b.count_ones() as u64).sum();
+        ones as f64 / self.header.num_bits as f64
+    }
+
+    /// Estimate the number of distinct items from the fill ratio.
+    ///
+    /// See [`BloomFilter::estimated_cardinality`](crate::bloom::BloomFilter::estimated_cardinality).
+    pub fn estimated_cardinality(&self) -> f64 {
+        cardinality_from_fill(self.fill_ratio(), self.num_bits(), self.header.num_hashes)
+    }
+
+    /// Current false positive rate measured from the actual fill ratio: `(X / m)^k`.
+    pub fn false_positive_rate_from_fill(&self) -> f64 {
+        self.fill_ratio().powi(self.header.num_hashes as i32)
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    fn from_mapping(map: Mapping) -> Result<Self, PersistError> {
+        let bytes = map.bytes();
+        let raw: &[u8; HEADER_LEN] = bytes
+            .get(..HEADER_LEN)
+            .and_then(|h| h.try_into().ok())
+            .ok_or(PersistError::Corrupt("file shorter than header"))?;
+        let header = BloomHeader::decode(raw)?;
+
+        let expected = header
+            .num_words()
+            .checked_mul(8)
+            .and_then(|len| len.checked_add(HEADER_LEN as u64))
+            .ok_or(PersistError::Corrupt("invalid bit count"))?;
+        if (bytes.len() as u64) < expected {
+            return Err(PersistError::Corrupt("file shorter than bit array"));
+        }
+
+        Ok(Self { map, header })
+    }
+
+    #[inline]
+    fn payload(&self) -> &[u8] {
+        let len = self.header.num_words() as usize * 8;
+        &self.map.bytes()[HEADER_LEN..HEADER_LEN + len]
+    }
+}
+
+impl MembershipQuery for MmapBloomFilter {
+    fn contains(&self, item: &str) -> bool {
+        MmapBloomFilter::contains(self, item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items_inserted()
+    }
+
+    fn fp_rate(&self) -> f64 {
+        self.false_positive_rate()
+    }
+
+    /// Size of the mapped bit array; the OS pages it in on demand.
+    fn memory_bytes(&self) -> usize {
+        self.payload().len()
+    }
+}
@@ -2,23 +2,180 @@
 //!
 //! This module canonicalizes URLs to ensure semantically equivalent
 //! URLs map to the same representation before deduplication.
+//!
+//! Paths, queries and fragments are brought into RFC 3986 §6.2.2
+//! normal form: escapes of unreserved characters are decoded, all
+//! other escapes use uppercase hex, and reserved characters keep their
+//! escaped or unescaped form, so the output is a valid URL naming the
+//! same resource as the input.
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 use url::Url;
 
+use crate::params::{ParamAction, ParamMatcher, ParamRule};
+use crate::session::SessionIdPolicy;
+
 /// Domain-specific normalization rule.
-type DomainRule = Box<dyn Fn(&Url) -> String>;
+///
+/// Rules are `Send + Sync` so a normalizer can be shared across threads.
+type DomainRule = Box<dyn Fn(&Url) -> String + Send + Sync>;
 
 /// Configuration flags controlling normalization behavior.
+///
+/// Every boolean flag defaults to `true`.
 #[derive(Clone, Debug)]
 pub struct NormalizerConfig {
+    /// Lowercase the scheme (`HTTP://` → `http://`); when off, the
+    /// scheme is kept as written in the input.
     pub lowercase_scheme: bool,
+    /// Strip a leading `www.` label from the host.
     pub remove_www: bool,
+    /// Drop the scheme's default port (`:80` for `http`) and keep any
+    /// other; when off, the port is always written out, default or not.
     pub remove_default_port: bool,
+    /// Sort query parameters by key, then value.
     pub sort_query_params: bool,
+    /// Drop the `#fragment`.
     pub remove_fragment: bool,
+    /// Lowercase the host. Hosts of special schemes (`http`, `https`,
+    /// `ws`, `wss`, `ftp`, `file`) are always lowercased by URL
+    /// parsing, so this only affects other schemes.
     pub lowercase_hostname: bool,
+    /// Path canonicalization for hosts without their own policy.
+    pub path: PathPolicy,
+    /// Path canonicalization per domain, keyed by domain name.
+    ///
+    /// A policy applies to the domain and all of its subdomains; the
+    /// most specific match wins.
+    pub domain_paths: HashMap<String, PathPolicy>,
+    /// Session identifiers stripped from paths and queries.
+    pub session_ids: SessionIdPolicy,
+}
+
+/// What to do with a trailing `/` on a non-root path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Keep the path as written.
+    Keep,
+    /// Remove trailing slashes (`/dir/` → `/dir`).
+    #[default]
+    Strip,
+    /// Append a slash to directory-like paths, i.e. those whose last
+    /// segment has no `.` extension (`/dir` → `/dir/`, `/a.html` kept).
+    Add,
+}
+
+/// Default documents served for a directory by common web servers.
+pub const DEFAULT_INDEX_FILES: &[&str] = &[
+    "index.html",
+    "index.htm",
+    "index.php",
+    "index.asp",
+    "index.aspx",
+    "default.htm",
+    "default.html",
+    "default.asp",
+    "default.aspx",
+];
+
+/// Path canonicalization policy.
+///
+/// The default only strips trailing slashes. Steps run in field order,
+/// so e.g. `/a//Index.HTML` with every option enabled and
+/// [`TrailingSlash::Strip`] becomes `/a`.
+///
+/// # Fields
+/// - `collapse_slashes`: Merge runs of `/` (`/a//b` → `/a/b`)
+/// - `lowercase`: Lowercase ASCII letters, for hosts that serve paths
+///   case-insensitively (e.g. IIS)
+/// - `index_files`: Final segments naming a directory's default
+///   document, removed to leave the directory (`/a/index.html` → `/a/`)
+/// - `trailing_slash`: Trailing slash handling
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathPolicy {
+    pub collapse_slashes: bool,
+    pub lowercase: bool,
+    pub index_files: Vec<String>,
+    pub trailing_slash: TrailingSlash,
+}
+
+impl PathPolicy {
+    /// Set the trailing slash handling.
+    pub fn with_trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Remove these default documents from the end of paths.
+    pub fn with_index_files(mut self, index_files: &[&str]) -> Self {
+        self.index_files = index_files.iter().map(|f| (*f).to_string()).collect();
+        self
+    }
+
+    /// Remove the [`DEFAULT_INDEX_FILES`] from the end of paths.
+    pub fn with_default_index_files(self) -> Self {
+        self.with_index_files(DEFAULT_INDEX_FILES)
+    }
+
+    /// Merge runs of `/` into one.
+    pub fn with_collapsed_slashes(mut self, enabled: bool) -> Self {
+        self.collapse_slashes = enabled;
+        self
+    }
+
+    /// Treat paths as case-insensitive by lowercasing them.
+    pub fn with_lowercase(mut self, enabled: bool) -> Self {
+        self.lowercase = enabled;
+        self
+    }
+
+    /// Canonicalize a path, which must start with `/`.
+    pub fn apply<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let mut path = Cow::Borrowed(path);
+
+        if self.collapse_slashes && path.contains("//") {
+            let mut collapsed = String::with_capacity(path.len());
+            for c in path.chars() {
+                if !(c == '/' && collapsed.ends_with('/')) {
+                    collapsed.push(c);
+                }
+            }
+            path = Cow::Owned(collapsed);
+        }
+
+        if self.lowercase && path.bytes().any(|b| b.is_ascii_uppercase()) {
+            path = Cow::Owned(path.to_ascii_lowercase());
+        }
+
+        let last = path.rfind('/').map_or(0, |i| i + 1);
+        if self.index_files.iter().any(|f| *f == path[last..]) {
+            path = Cow::Owned(path[..last].to_string());
+        }
+
+        match self.trailing_slash {
+            TrailingSlash::Keep => {}
+            TrailingSlash::Strip => {
+                let trimmed = path.trim_end_matches('/');
+                if trimmed.len() < path.len() {
+                    path = Cow::Owned(trimmed.to_string());
+                }
+            }
+            TrailingSlash::Add => {
+                let last = &path[path.rfind('/').map_or(0, |i| i + 1)..];
+                if !last.is_empty() && !last.contains('.') {
+                    path.to_mut().push('/');
+                }
+            }
+        }
+
+        if path.is_empty() {
+            Cow::Borrowed("/")
+        } else {
+            path
+        }
+    }
 }
 
 /// URL normalizer.
@@ -27,13 +184,56 @@ pub struct NormalizerConfig {
 /// before hashing or deduplication.
 pub struct UrlNormalizer {
     tracking_params: HashSet<String>,
+    param_rules: Vec<ParamRule>,
     domain_rules: HashMap<String, DomainRule>,
     config: NormalizerConfig,
 }
 
+impl Default for NormalizerConfig {
+    fn default() -> Self {
+        Self {
+            lowercase_scheme: true,
+            remove_www: true,
+            remove_default_port: true,
+            sort_query_params: true,
+            remove_fragment: true,
+            lowercase_hostname: true,
+            path: PathPolicy::default(),
+            domain_paths: HashMap::new(),
+            session_ids: SessionIdPolicy::default(),
+        }
+    }
+}
+
 impl UrlNormalizer {
     /// Create a new URL normalizer with default settings.
     pub fn new() -> Self {
+        Self::with_config(NormalizerConfig::default())
+    }
+
+    /// Start building a normalizer from the default configuration.
+    ///
+    /// ```
+    /// use kaka::UrlNormalizer;
+    ///
+    /// let normalizer = UrlNormalizer::builder()
+    ///     .remove_tracking_params(&["session"])
+    ///     .remove_fragment(false)
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     normalizer.normalize("HTTPS://Example.com/page?session=1&b=2&a=1#top").unwrap(),
+    ///     "https://example.com/page?a=1&b=2#top"
+    /// );
+    /// ```
+    pub fn builder() -> NormalizerBuilder {
+        NormalizerBuilder {
+            normalizer: Self::new(),
+        }
+    }
+
+    /// Create a URL normalizer with the given configuration.
+    pub fn with_config(config: NormalizerConfig) -> Self {
         let tracking_params = DEFAULT_TRACKING_PARAMS
             .iter()
             .map(|p| (*p).to_string())
@@ -41,18 +241,17 @@ impl UrlNormalizer {
 
         Self {
             tracking_params,
+            param_rules: Vec::new(),
             domain_rules: HashMap::new(),
-            config: NormalizerConfig {
-                lowercase_scheme: true,
-                remove_www: true,
-                remove_default_port: true,
-                sort_query_params: true,
-                remove_fragment: true,
-                lowercase_hostname: true,
-            },
+            config,
         }
     }
 
+    /// Access the active configuration.
+    pub fn config(&self) -> &NormalizerConfig {
+        &self.config
+    }
+
     /// Normalize a URL into its canonical representation.
     pub fn normalize(&self, input: &str) -> Result<String, url::ParseError> {
         let url = Url::parse(input)?;
@@ -64,44 +263,96 @@ impl UrlNormalizer {
 
         let mut out = String::with_capacity(input.len());
 
-        // Scheme
-        out.push_str(url.scheme());
-        out.push_str("://");
+        // Scheme; parsing always lowercases it, so the original
+        // spelling is recovered from the input
+        if self.config.lowercase_scheme {
+            out.push_str(url.scheme());
+        } else {
+            let raw = input
+                .trim_start_matches(|c: char| c <= ' ')
+                .get(..url.scheme().len())
+                .filter(|raw| raw.eq_ignore_ascii_case(url.scheme()));
+            out.push_str(raw.unwrap_or(url.scheme()));
+        }
+
+        // Opaque paths such as `mailto:x@y` have no hierarchy for the
+        // path policies to act on
+        if url.cannot_be_a_base() {
+            out.push(':');
+            push_percent_normalized(&mut out, url.path(), PATH_SAFE);
+            self.push_query_and_fragment(&mut out, &url, "");
+            return Ok(out);
+        }
+        // An empty authority, as in `file:///tmp`, is still an authority
+        let has_authority = url.as_str()[url.scheme().len() + 1..].starts_with("//");
+        out.push_str(if has_authority { "://" } else { ":" });
 
         // Host
-        if let Some(host) = url.host_str() {
-            let mut host = host.to_string();
-            if self.config.lowercase_hostname {
-                host.make_ascii_lowercase();
-            }
-            if self.config.remove_www {
-                host = host.strip_prefix("www.").unwrap_or(&host).to_string();
-            }
-            out.push_str(&host);
+        let mut host = url.host_str().unwrap_or("").to_string();
+        if self.config.lowercase_hostname {
+            host.make_ascii_lowercase();
+        }
+        if self.config.remove_www {
+            host = host.strip_prefix("www.").unwrap_or(&host).to_string();
         }
+        out.push_str(&host);
 
-        // Port
-        if !self.config.remove_default_port
-            && let Some(port) = url.port()
-        {
+        // Port; `Url::port` is already `None` for the default port
+        let port = if self.config.remove_default_port {
+            url.port()
+        } else {
+            url.port_or_known_default()
+        };
+        if let Some(port) = port {
             out.push(':');
             out.push_str(&port.to_string());
         }
 
-        // Path
-        let path = url.path().trim_end_matches('/');
-        if path.is_empty() {
-            out.push('/');
-        } else {
-            out.push_str(path);
-        }
+        // Path, in normal form first so escaped and plain spellings of a
+        // session parameter or index file are treated alike. Normalizing
+        // again restores uppercase hex digits after a lowercase policy
+        let path = percent_normalized(url.path(), PATH_SAFE);
+        let path = self.config.session_ids.strip_path(&path);
+        let path = self.path_policy_for(&host).apply(&path);
+        push_percent_normalized(&mut out, &path, PATH_SAFE);
+
+        self.push_query_and_fragment(&mut out, &url, &host);
+        Ok(out)
+    }
 
-        // Query parameters
-        if url.query().is_some() {
-            let mut params: Vec<(String, String)> = url
-                .query_pairs()
-                .filter(|(k, _)| !self.tracking_params.contains(k.as_ref()))
-                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+    /// Append the filtered query and, unless removed, the fragment.
+    fn push_query_and_fragment(&self, out: &mut String, url: &Url, host: &str) {
+        // Query parameters, kept in their encoded form so an escaped
+        // delimiter such as `%26` is never confused with a real one
+        if let Some(query) = url.query() {
+            let rules: Vec<&ParamRule> = self
+                .param_rules
+                .iter()
+                .filter(|rule| rule.applies_to(host))
+                .collect();
+            let is_tracking = |key: &str| {
+                let matched = |action| {
+                    rules
+                        .iter()
+                        .any(|rule| rule.action == action && rule.matcher.matches(key))
+                };
+                (self.tracking_params.contains(key) || matched(ParamAction::Remove))
+                    && !matched(ParamAction::Keep)
+            };
+
+            let mut params: Vec<(String, Option<String>)> = query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| match pair.split_once('=') {
+                    Some((k, v)) => (
+                        percent_normalized(k, QUERY_SAFE),
+                        Some(percent_normalized(v, QUERY_SAFE)),
+                    ),
+                    None => (percent_normalized(pair, QUERY_SAFE), None),
+                })
+                .filter(|(k, v)| {
+                    !is_tracking(k) && !self.config.session_ids.is_session_param(k, v.as_deref())
+                })
                 .collect();
 
             if self.config.sort_query_params {
@@ -115,14 +366,21 @@ impl UrlNormalizer {
                         out.push('&');
                     }
                     out.push_str(k);
-                    out.push('=');
-                    out.push_str(v);
+                    if let Some(v) = v {
+                        out.push('=');
+                        out.push_str(v);
+                    }
                 }
             }
         }
 
-        // Fragment intentionally dropped if configured
-        Ok(out)
+        // Fragment
+        if !self.config.remove_fragment
+            && let Some(fragment) = url.fragment()
+        {
+            out.push('#');
+            push_percent_normalized(out, fragment, QUERY_SAFE);
+        }
     }
 
     /// Add a tracking query parameter to be removed during normalization.
@@ -133,10 +391,72 @@ impl UrlNormalizer {
     /// Add a domain-specific normalization rule.
     pub fn add_domain_rule<F>(&mut self, domain: &str, rule: F)
     where
-        F: Fn(&Url) -> String + 'static,
+        F: Fn(&Url) -> String + Send + Sync + 'static,
     {
         self.domain_rules.insert(domain.to_string(), Box::new(rule));
     }
+
+    /// Remove query parameters selected by `matcher` on every site.
+    pub fn remove_params(&mut self, matcher: ParamMatcher) {
+        self.add_param_rule(matcher, None, ParamAction::Remove);
+    }
+
+    /// Remove query parameters selected by `matcher` on a registrable
+    /// domain and its subdomains.
+    pub fn remove_params_for_domain(&mut self, domain: &str, matcher: ParamMatcher) {
+        self.add_param_rule(matcher, Some(domain), ParamAction::Remove);
+    }
+
+    /// Keep query parameters selected by `matcher` on a registrable
+    /// domain and its subdomains, overriding every removal rule and the
+    /// exact tracking parameters. Session IDs are still stripped; see
+    /// [`SessionIdPolicy::allowlist`] for those.
+    pub fn keep_params_for_domain(&mut self, domain: &str, matcher: ParamMatcher) {
+        self.add_param_rule(matcher, Some(domain), ParamAction::Keep);
+    }
+
+    /// Remove query parameters whose key satisfies `predicate`, on every
+    /// site.
+    pub fn remove_params_matching<F>(&mut self, predicate: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.remove_params(ParamMatcher::predicate(predicate));
+    }
+
+    /// Use a path policy for a domain and its subdomains.
+    pub fn set_domain_path_policy(&mut self, domain: &str, policy: PathPolicy) {
+        self.config
+            .domain_paths
+            .insert(domain.to_ascii_lowercase(), policy);
+    }
+
+    fn add_param_rule(&mut self, matcher: ParamMatcher, domain: Option<&str>, action: ParamAction) {
+        self.param_rules.push(ParamRule {
+            matcher,
+            domain: domain.map(str::to_ascii_lowercase),
+            action,
+        });
+    }
+
+    /// Path policy of the most specific domain matching `host`, or the
+    /// global one.
+    pub fn path_policy_for(&self, host: &str) -> &PathPolicy {
+        if !self.config.domain_paths.is_empty() {
+            let host = host.to_ascii_lowercase();
+            let mut suffix = host.as_str();
+            loop {
+                if let Some(policy) = self.config.domain_paths.get(suffix) {
+                    return policy;
+                }
+                match suffix.split_once('.') {
+                    Some((_, parent)) => suffix = parent,
+                    None => break,
+                }
+            }
+        }
+        &self.config.path
+    }
 }
 
 impl Default for UrlNormalizer {
@@ -145,6 +465,201 @@ impl Default for UrlNormalizer {
     }
 }
 
+/// Fluent builder for [`UrlNormalizer`], created by
+/// [`UrlNormalizer::builder`].
+///
+/// Starts from [`NormalizerConfig::default`] and the default tracking
+/// parameters; each flag method overrides one [`NormalizerConfig`] field.
+pub struct NormalizerBuilder {
+    normalizer: UrlNormalizer,
+}
+
+impl NormalizerBuilder {
+    /// Replace every flag with the given configuration.
+    pub fn config(mut self, config: NormalizerConfig) -> Self {
+        self.normalizer.config = config;
+        self
+    }
+
+    /// Set [`NormalizerConfig::lowercase_scheme`].
+    pub fn lowercase_scheme(mut self, enabled: bool) -> Self {
+        self.normalizer.config.lowercase_scheme = enabled;
+        self
+    }
+
+    /// Set [`NormalizerConfig::remove_www`].
+    pub fn remove_www(mut self, enabled: bool) -> Self {
+        self.normalizer.config.remove_www = enabled;
+        self
+    }
+
+    /// Set [`NormalizerConfig::remove_default_port`].
+    pub fn remove_default_port(mut self, enabled: bool) -> Self {
+        self.normalizer.config.remove_default_port = enabled;
+        self
+    }
+
+    /// Set [`NormalizerConfig::sort_query_params`].
+    pub fn sort_query_params(mut self, enabled: bool) -> Self {
+        self.normalizer.config.sort_query_params = enabled;
+        self
+    }
+
+    /// Set [`NormalizerConfig::remove_fragment`].
+    pub fn remove_fragment(mut self, enabled: bool) -> Self {
+        self.normalizer.config.remove_fragment = enabled;
+        self
+    }
+
+    /// Set [`NormalizerConfig::lowercase_hostname`].
+    pub fn lowercase_hostname(mut self, enabled: bool) -> Self {
+        self.normalizer.config.lowercase_hostname = enabled;
+        self
+    }
+
+    /// Set the [`NormalizerConfig::session_ids`] policy.
+    pub fn session_id_policy(mut self, policy: SessionIdPolicy) -> Self {
+        self.normalizer.config.session_ids = policy;
+        self
+    }
+
+    /// Set the global [`NormalizerConfig::path`] policy.
+    pub fn path_policy(mut self, policy: PathPolicy) -> Self {
+        self.normalizer.config.path = policy;
+        self
+    }
+
+    /// Use a path policy for a domain and its subdomains.
+    pub fn domain_path_policy(mut self, domain: &str, policy: PathPolicy) -> Self {
+        self.normalizer.set_domain_path_policy(domain, policy);
+        self
+    }
+
+    /// Remove these query parameters in addition to the defaults.
+    pub fn remove_tracking_params(mut self, params: &[&str]) -> Self {
+        for param in params {
+            self.normalizer.add_tracking_param(param);
+        }
+        self
+    }
+
+    /// Keep every query parameter, including the default tracking ones.
+    ///
+    /// Rules added afterwards still apply.
+    pub fn keep_all_params(mut self) -> Self {
+        self.normalizer.tracking_params.clear();
+        self.normalizer.param_rules.clear();
+        self
+    }
+
+    /// Remove query parameters selected by `matcher` on every site.
+    pub fn remove_params(mut self, matcher: ParamMatcher) -> Self {
+        self.normalizer.remove_params(matcher);
+        self
+    }
+
+    /// Remove query parameters selected by `matcher` on a registrable
+    /// domain and its subdomains.
+    pub fn remove_params_for_domain(mut self, domain: &str, matcher: ParamMatcher) -> Self {
+        self.normalizer.remove_params_for_domain(domain, matcher);
+        self
+    }
+
+    /// Keep query parameters selected by `matcher` on a registrable
+    /// domain and its subdomains, overriding removal rules.
+    pub fn keep_params_for_domain(mut self, domain: &str, matcher: ParamMatcher) -> Self {
+        self.normalizer.keep_params_for_domain(domain, matcher);
+        self
+    }
+
+    /// Remove query parameters whose key satisfies `predicate`.
+    pub fn remove_params_matching<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.normalizer.remove_params_matching(predicate);
+        self
+    }
+
+    /// Add a domain-specific normalization rule.
+    pub fn add_domain_rule<F>(mut self, domain: &str, rule: F) -> Self
+    where
+        F: Fn(&Url) -> String + Send + Sync + 'static,
+    {
+        self.normalizer.add_domain_rule(domain, rule);
+        self
+    }
+
+    /// Finish building the normalizer.
+    pub fn build(self) -> UrlNormalizer {
+        self.normalizer
+    }
+}
+
+/// Reserved characters left unescaped in a path (RFC 3986 `pchar` and `/`).
+const PATH_SAFE: &[u8] = b"!$&'()*+,;=:@/";
+
+/// Reserved characters left unescaped in a query or fragment.
+const QUERY_SAFE: &[u8] = b"!$&'()*+,;=:@/?";
+
+/// [`push_percent_normalized`] into a new string.
+fn percent_normalized(raw: &str, safe: &[u8]) -> String {
+    let mut out = String::with_capacity(raw.len());
+    push_percent_normalized(&mut out, raw, safe);
+    out
+}
+
+/// Append a URL component in RFC 3986 §6.2.2 normal form.
+///
+/// - Escapes of unreserved characters (`A-Z a-z 0-9 - . _ ~`) are decoded
+/// - Other escapes are kept, with uppercase hex digits
+/// - Any byte that is neither unreserved nor in `safe` is escaped,
+///   including a `%` that does not start a valid escape
+fn push_percent_normalized(out: &mut String, raw: &str, safe: &[u8]) {
+    let bytes = raw.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let escaped = (b == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+
+        match escaped {
+            Some(decoded) if is_unreserved(decoded) => {
+                out.push(decoded as char);
+                i += 3;
+            }
+            Some(decoded) => {
+                push_escape(out, decoded);
+                i += 3;
+            }
+            None if is_unreserved(b) || safe.contains(&b) => {
+                out.push(b as char);
+                i += 1;
+            }
+            None => {
+                push_escape(out, b);
+                i += 1;
+            }
+        }
+    }
+}
+
+#[inline]
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+#[inline]
+fn push_escape(out: &mut String, b: u8) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    out.push('%');
+    out.push(HEX[(b >> 4) as usize] as char);
+    out.push(HEX[(b & 0xF) as usize] as char);
+}
+
 /// Default tracking parameters removed during normalization.
 const DEFAULT_TRACKING_PARAMS: &[&str] = &[
     "utm_source",
@@ -152,6 +667,24 @@ const DEFAULT_TRACKING_PARAMS: &[&str] = &[
     "utm_campaign",
     "utm_content",
     "utm_term",
+    "utm_id",
+    "pk_campaign",
+    "pk_kwd",
+    "pk_source",
+    "pk_medium",
+    "pk_content",
+    "hsa_acc",
+    "hsa_cam",
+    "hsa_grp",
+    "hsa_ad",
+    "hsa_src",
+    "hsa_tgt",
+    "hsa_kw",
+    "hsa_mt",
+    "hsa_net",
+    "hsa_ver",
+    "_hsenc",
+    "_hsmi",
     "fbclid",
     "gclid",
     "msclkid",
@@ -159,6 +692,7 @@ const DEFAULT_TRACKING_PARAMS: &[&str] = &[
     "_gl",
     "mc_cid",
     "mc_eid",
+    "mkt_tok",
     "ref",
     "referrer",
 ];
@@ -0,0 +1,180 @@
+//! Pattern-based query parameter rules.
+//!
+//! Ad and analytics networks add parameters by family (`utm_*`, `pk_*`,
+//! `hsa_*`, `_hs*`) faster than exact lists can follow. A
+//! [`ParamMatcher`] selects parameter keys by exact name, prefix, glob,
+//! regular expression or predicate. [`UrlNormalizer`](crate::UrlNormalizer)
+//! removes keys matched by its removal rules unless a keep rule for the
+//! same site matches too, so a site that relies on e.g. `ref` can opt
+//! out of a global removal.
+//!
+//! By default only known tracking keys are removed. Whole families are
+//! opt-in, as a prefix such as `pk_` also starts ordinary keys:
+//!
+//! ```
+//! use kaka::{ParamMatcher, UrlNormalizer};
+//! use kaka::params::TRACKING_PREFIXES;
+//!
+//! let normalizer = TRACKING_PREFIXES
+//!     .iter()
+//!     .fold(UrlNormalizer::builder(), |builder, prefix| {
+//!         builder.remove_params(ParamMatcher::prefix(prefix))
+//!     })
+//!     .build();
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use regex::Regex;
+
+/// Key prefixes of common tracking parameter families.
+///
+/// Not removed by default; see the [module docs](self) to opt in.
+pub const TRACKING_PREFIXES: &[&str] = &["utm_", "pk_", "hsa_", "_hs"];
+
+/// Predicate over parameter keys.
+type KeyPredicate = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Selects query parameters by key.
+///
+/// Keys are matched case-sensitively after percent-encoding
+/// normalization, so `utm%5Fsource` is matched as `utm_source`.
+#[derive(Clone)]
+pub enum ParamMatcher {
+    /// The key equals this string.
+    Exact(String),
+    /// The key starts with this string.
+    Prefix(String),
+    /// The key matches a glob where `*` matches any run of characters
+    /// and `?` exactly one.
+    Glob(String),
+    /// The regular expression matches the key (use `^…$` to anchor).
+    Regex(Regex),
+    /// The function returns `true` for the key.
+    Predicate(KeyPredicate),
+}
+
+impl ParamMatcher {
+    /// Match keys equal to `key`.
+    pub fn exact(key: &str) -> Self {
+        ParamMatcher::Exact(key.to_string())
+    }
+
+    /// Match keys starting with `prefix`.
+    pub fn prefix(prefix: &str) -> Self {
+        ParamMatcher::Prefix(prefix.to_string())
+    }
+
+    /// Match keys against a `*` / `?` glob, e.g. `_hs*`.
+    pub fn glob(pattern: &str) -> Self {
+        ParamMatcher::Glob(pattern.to_string())
+    }
+
+    /// Match keys against a regular expression.
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(ParamMatcher::Regex)
+    }
+
+    /// Match keys for which `predicate` returns `true`.
+    pub fn predicate<F>(predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        ParamMatcher::Predicate(Arc::new(predicate))
+    }
+
+    /// Whether the matcher selects `key`.
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            ParamMatcher::Exact(exact) => key == exact,
+            ParamMatcher::Prefix(prefix) => key.starts_with(prefix.as_str()),
+            ParamMatcher::Glob(pattern) => glob_matches(pattern.as_bytes(), key.as_bytes()),
+            ParamMatcher::Regex(regex) => regex.is_match(key),
+            ParamMatcher::Predicate(predicate) => predicate(key),
+        }
+    }
+}
+
+impl fmt::Debug for ParamMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamMatcher::Exact(key) => f.debug_tuple("Exact").field(key).finish(),
+            ParamMatcher::Prefix(prefix) => f.debug_tuple("Prefix").field(prefix).finish(),
+            ParamMatcher::Glob(pattern) => f.debug_tuple("Glob").field(pattern).finish(),
+            ParamMatcher::Regex(regex) => f.debug_tuple("Regex").field(&regex.as_str()).finish(),
+            ParamMatcher::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+/// Whether a rule removes or keeps the parameters it matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ParamAction {
+    Remove,
+    Keep,
+}
+
+/// A matcher, the sites it applies to and what it does.
+///
+/// # Fields
+/// - `matcher`: Keys the rule selects
+/// - `domain`: Registrable domain the rule is limited to (the domain
+///   and its subdomains), or `None` for every site
+/// - `action`: Whether selected keys are removed or kept
+#[derive(Clone, Debug)]
+pub(crate) struct ParamRule {
+    pub(crate) matcher: ParamMatcher,
+    pub(crate) domain: Option<String>,
+    pub(crate) action: ParamAction,
+}
+
+impl ParamRule {
+    /// Whether the rule applies to URLs on `host`.
+    pub(crate) fn applies_to(&self, host: &str) -> bool {
+        self.domain
+            .as_deref()
+            .is_none_or(|domain| domain_matches(host, domain))
+    }
+}
+
+// ----------------------------------------------------------------
+// Internal helpers
+// ----------------------------------------------------------------
+
+/// Whether `host` is `domain` or one of its subdomains.
+pub(crate) fn domain_matches(host: &str, domain: &str) -> bool {
+    host.len() >= domain.len()
+        && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+        && (host.len() == domain.len() || host.as_bytes()[host.len() - domain.len() - 1] == b'.')
+}
+
+/// Iterative glob match with single-star backtracking.
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` absorb one more character
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
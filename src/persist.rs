@@ -0,0 +1,127 @@
+//! Shared pieces of the on-disk formats.
+//!
+//! Every persisted structure starts with a fixed-size little-endian
+//! header carrying a magic tag, a format version and an XXH3 checksum,
+//! so truncated, corrupt or foreign files are rejected on load.
+
+use std::fmt;
+use std::io::{self, Read};
+
+/// Errors raised while loading a persisted structure.
+#[derive(Debug)]
+pub enum PersistError {
+    /// Underlying I/O failure (including unexpected end of file).
+    Io(io::Error),
+    /// The file does not start with the expected magic tag.
+    BadMagic,
+    /// The file was written by an unsupported format version.
+    UnsupportedVersion(u16),
+    /// The stored checksum does not match the contents.
+    ChecksumMismatch,
+    /// The header describes an impossible structure.
+    Corrupt(&'static str),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "I/O error: {}", err),
+            PersistError::BadMagic => write!(f, "not a kaka file (bad magic)"),
+            PersistError::UnsupportedVersion(v) => {
+                write!(f, "unsupported format version {}", v)
+            }
+            PersistError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            PersistError::Corrupt(reason) => write!(f, "corrupt file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        PersistError::Io(err)
+    }
+}
+
+/// Read exactly `words` little-endian `u64`s.
+///
+/// Reads in bounded chunks so a corrupt length field cannot trigger a
+/// huge up-front allocation.
+pub(crate) fn read_words<R: Read>(reader: &mut R, words: u64) -> io::Result<Vec<u64>> {
+    const CHUNK_WORDS: usize = 8192;
+
+    let mut out = Vec::new();
+    let mut buf = [0u8; CHUNK_WORDS * 8];
+    let mut remaining = words;
+
+    while remaining > 0 {
+        let n = remaining.min(CHUNK_WORDS as u64) as usize;
+        reader.read_exact(&mut buf[..n * 8])?;
+        out.extend(
+            buf[..n * 8]
+                .chunks_exact(8)
+                .map(|b| u64::from_le_bytes(b.try_into().expect("8-byte chunk"))),
+        );
+        remaining -= n as u64;
+    }
+
+    Ok(out)
+}
+
+/// Read exactly `len` bytes, in bounded chunks like [`read_words`].
+pub(crate) fn read_bytes<R: Read>(reader: &mut R, len: u64) -> io::Result<Vec<u8>> {
+    const CHUNK_BYTES: u64 = 64 * 1024;
+
+    let mut out = Vec::new();
+    let mut remaining = len;
+
+    while remaining > 0 {
+        let n = remaining.min(CHUNK_BYTES);
+        let start = out.len();
+        out.resize(start + n as usize, 0);
+        reader.read_exact(&mut out[start..])?;
+        remaining -= n;
+    }
+
+    Ok(out)
+}
+
+/// Little-endian field reader over a fixed-size header buffer.
+pub(crate) struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> HeaderReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub(crate) fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let out = self.bytes[self.pos..self.pos + N]
+            .try_into()
+            .expect("header field within bounds");
+        self.pos += N;
+        out
+    }
+
+    pub(crate) fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.bytes())
+    }
+
+    pub(crate) fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.bytes())
+    }
+
+    pub(crate) fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+}
@@ -0,0 +1,189 @@
+//! Scalable Bloom filter (Almeida et al., 2007).
+//!
+//! A plain [`BloomFilter`] is sized for a fixed capacity; past it, the
+//! false positive rate silently climbs toward 1.0. A scalable filter
+//! starts with one sub-filter and adds a new, larger one each time the
+//! current stage reaches its capacity. Stage `i` is sized for
+//! `p0 * r^i`, so the compound false positive rate stays below
+//! `p0 / (1 - r)` however many items arrive.
+
+use crate::bloom::BloomFilter;
+use crate::hash::DEFAULT_SEED;
+
+/// Default capacity multiplier between consecutive stages (s).
+pub const DEFAULT_GROWTH_FACTOR: usize = 2;
+
+/// Default false-positive tightening ratio between stages (r).
+pub const DEFAULT_TIGHTENING_RATIO: f64 = 0.85;
+
+/// Bloom filter that grows past its initial capacity.
+///
+/// # Characteristics
+/// - No false negatives
+/// - Overall false positive rate bounded by the configured `fp_rate`
+/// - Capacity grows geometrically; `n` need not be known up front
+///
+/// # Fields
+/// - `stages`: Sub-filters, oldest first
+/// - `fp_rate`: Overall false positive bound (P)
+/// - `growth_factor`: Capacity multiplier between stages (s)
+/// - `tightening_ratio`: FP multiplier between stages (r)
+/// - `seed`: Hash seed shared by every stage
+pub struct ScalableBloomFilter {
+    stages: Vec<Stage>,
+    fp_rate: f64,
+    growth_factor: usize,
+    tightening_ratio: f64,
+    seed: u64,
+}
+
+/// One fixed-size sub-filter and its fill level.
+struct Stage {
+    filter: BloomFilter,
+    capacity: usize,
+    inserted: usize,
+    fp_rate: f64,
+}
+
+impl ScalableBloomFilter {
+    /// Create a scalable filter with default growth parameters.
+    ///
+    /// # Arguments
+    /// - `initial_capacity`: Capacity of the first stage
+    /// - `fp_rate`: Overall false positive bound (P)
+    pub fn new(initial_capacity: usize, fp_rate: f64) -> Self {
+        Self::with_params(
+            initial_capacity,
+            fp_rate,
+            DEFAULT_GROWTH_FACTOR,
+            DEFAULT_TIGHTENING_RATIO,
+            DEFAULT_SEED,
+        )
+    }
+
+    /// Create a scalable filter with explicit growth parameters.
+    ///
+    /// # Arguments
+    /// - `initial_capacity`: Capacity of the first stage
+    /// - `fp_rate`: Overall false positive bound (P)
+    /// - `growth_factor`: Capacity multiplier between stages (s ≥ 1)
+    /// - `tightening_ratio`: FP multiplier between stages (0 < r < 1)
+    /// - `seed`: Hash seed shared by every stage
+    ///
+    /// The first stage is sized for `p0 = P * (1 - r)`.
+    pub fn with_params(
+        initial_capacity: usize,
+        fp_rate: f64,
+        growth_factor: usize,
+        tightening_ratio: f64,
+        seed: u64,
+    ) -> Self {
+        assert!(initial_capacity > 0, "Initial capacity must be positive");
+        assert!(growth_factor >= 1, "Growth factor must be at least 1");
+        assert!(
+            tightening_ratio > 0.0 && tightening_ratio < 1.0,
+            "Tightening ratio must be in (0, 1)"
+        );
+
+        let first_fp = fp_rate * (1.0 - tightening_ratio);
+
+        Self {
+            stages: vec![Stage::new(initial_capacity, first_fp, seed)],
+            fp_rate,
+            growth_factor,
+            tightening_ratio,
+            seed,
+        }
+    }
+
+    /// Insert an element, adding a new stage first if the current one is full.
+    pub fn insert(&mut self, value: &str) {
+        if self.current().inserted >= self.current().capacity {
+            self.grow();
+        }
+
+        let stage = self.stages.last_mut().expect("at least one stage");
+        stage.filter.insert(value);
+        stage.inserted += 1;
+    }
+
+    /// Check whether an element is possibly in the set.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        // Newest stages are largest and most likely to hold recent items
+        self.stages.iter().rev().any(|s| s.filter.contains(value))
+    }
+
+    /// Estimate the current compound false positive rate.
+    ///
+    /// Formula:
+    /// `1 - Π(1 - p_i)` over the current rate `p_i` of each stage
+    pub fn false_positive_rate(&self) -> f64 {
+        1.0 - self
+            .stages
+            .iter()
+            .map(|s| 1.0 - s.filter.false_positive_rate())
+            .product::<f64>()
+    }
+
+    /// Configured overall false positive bound (P).
+    pub fn false_positive_bound(&self) -> f64 {
+        self.fp_rate
+    }
+
+    /// Number of sub-filters allocated so far.
+    pub fn num_stages(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Combined capacity of all stages.
+    pub fn capacity(&self) -> usize {
+        self.stages.iter().map(|s| s.capacity).sum()
+    }
+
+    /// Number of insert calls made so far (n).
+    pub fn items_inserted(&self) -> u64 {
+        self.stages.iter().map(|s| s.inserted as u64).sum()
+    }
+
+    /// Total size of all stage bit arrays, in bits.
+    pub fn num_bits(&self) -> usize {
+        self.stages.iter().map(|s| s.filter.num_bits()).sum()
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    #[inline]
+    fn current(&self) -> &Stage {
+        self.stages.last().expect("at least one stage")
+    }
+
+    fn grow(&mut self) {
+        let last = self.current();
+        let capacity = last.capacity.saturating_mul(self.growth_factor);
+        let fp_rate = last.fp_rate * self.tightening_ratio;
+
+        self.stages.push(Stage::new(capacity, fp_rate, self.seed));
+    }
+}
+
+impl Stage {
+    fn new(capacity: usize, fp_rate: f64, seed: u64) -> Self {
+        Self {
+            filter: BloomFilter::with_seed(capacity, fp_rate, seed),
+            capacity,
+            inserted: 0,
+            fp_rate,
+        }
+    }
+}
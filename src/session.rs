@@ -0,0 +1,226 @@
+//! Session-ID detection.
+//!
+//! Servers that track sessions in the URL instead of a cookie produce a
+//! new URL for every visit: `/cart;jsessionid=1A2B…`, `?PHPSESSID=…`,
+//! `/(S(lit3py55t21z5v55vlm25s55))/page.aspx`. To a crawler each one is
+//! a fresh page, so a single site becomes an unbounded crawler trap.
+//! [`SessionIdPolicy`] decides which path parameters, path segments and
+//! query parameters carry a session and are dropped by
+//! [`UrlNormalizer`](crate::UrlNormalizer).
+
+use std::borrow::Cow;
+
+/// Parameter names (compared case-insensitively) that carry a session.
+pub const DEFAULT_SESSION_PARAMS: &[&str] = &[
+    "jsessionid",
+    "phpsessid",
+    "sid",
+    "sessid",
+    "sessionid",
+    "session_id",
+    "cfid",
+    "cftoken",
+    "zenid",
+    "oscsid",
+];
+
+/// Parameter name prefixes that carry a session, e.g. IIS's
+/// `ASPSESSIONIDQQGGQGPG`.
+pub const DEFAULT_SESSION_PREFIXES: &[&str] = &["aspsessionid"];
+
+/// Shortest parameter value considered a session token.
+const MIN_TOKEN_LEN: usize = 16;
+
+/// Longest parameter value considered a session token.
+const MAX_TOKEN_LEN: usize = 128;
+
+/// Which session identifiers to strip from URLs.
+///
+/// # Fields
+/// - `enabled`: Strip parameters named in [`DEFAULT_SESSION_PARAMS`] or
+///   starting with one of [`DEFAULT_SESSION_PREFIXES`], from the query
+///   and from `;name=value` path parameters, and ASP.NET cookieless
+///   session segments such as `/(S(…))/`
+/// - `detect_tokens`: Also strip parameters of any name whose value
+///   looks like a random session token (see
+///   [`looks_like_token`](Self::looks_like_token)). Off by default, as
+///   it also matches other random identifiers such as commit hashes
+/// - `allowlist`: Parameter names never stripped (case-insensitive),
+///   for sites where e.g. `sid` is a story ID
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionIdPolicy {
+    pub enabled: bool,
+    pub detect_tokens: bool,
+    pub allowlist: Vec<String>,
+}
+
+impl Default for SessionIdPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            detect_tokens: false,
+            allowlist: Vec::new(),
+        }
+    }
+}
+
+impl SessionIdPolicy {
+    /// Policy that strips nothing.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Enable or disable token detection on parameter values.
+    pub fn with_token_detection(mut self, enabled: bool) -> Self {
+        self.detect_tokens = enabled;
+        self
+    }
+
+    /// Never strip parameters with these names.
+    pub fn with_allowlist(mut self, names: &[&str]) -> Self {
+        self.allowlist
+            .extend(names.iter().map(|name| name.to_ascii_lowercase()));
+        self
+    }
+
+    /// Whether a `name=value` parameter carries a session.
+    pub fn is_session_param(&self, name: &str, value: Option<&str>) -> bool {
+        if !self.enabled || self.allowlist.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+            return false;
+        }
+
+        is_session_name(name) || self.detect_tokens && value.is_some_and(Self::looks_like_token)
+    }
+
+    /// Whether a value looks like a random session token.
+    ///
+    /// Heuristic: 16 to 128 characters from the hex / base64 / base64url
+    /// alphabet, at least one digit, and a character class (upper,
+    /// lower, digit, symbol) change at no fewer than a third of the
+    /// positions. Random tokens change class about every other
+    /// character; words and numbers (`SummerSale2024`,
+    /// `12345678901234567`) rarely do. Values are seen percent-encoded,
+    /// so `%2B`, `%2F` and `%3D` count as `+`, `/` and `=`.
+    pub fn looks_like_token(value: &str) -> bool {
+        let decoded = decode_base64_escapes(value);
+        let end = decoded
+            .iter()
+            .rposition(|&b| b != b'=')
+            .map_or(0, |i| i + 1);
+        let bytes = &decoded[..end];
+        if !(MIN_TOKEN_LEN..=MAX_TOKEN_LEN).contains(&bytes.len())
+            || !bytes
+                .iter()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_'))
+            || !bytes.iter().any(u8::is_ascii_digit)
+        {
+            return false;
+        }
+
+        let transitions = bytes
+            .windows(2)
+            .filter(|pair| char_class(pair[0]) != char_class(pair[1]))
+            .count();
+        transitions * 3 >= bytes.len() - 1
+    }
+
+    /// Remove session path parameters and cookieless session segments.
+    pub fn strip_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        if !self.enabled || !(path.contains(';') || path.contains("S(")) {
+            return Cow::Borrowed(path);
+        }
+
+        let mut out = String::with_capacity(path.len());
+        for (i, segment) in path.split('/').enumerate() {
+            if is_cookieless_segment(segment) {
+                continue;
+            }
+            if i > 0 {
+                out.push('/');
+            }
+
+            let mut parts = segment.split(';');
+            out.push_str(parts.next().unwrap_or(""));
+            for param in parts {
+                let (name, value) = match param.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (param, None),
+                };
+                if !self.is_session_param(name, value) {
+                    out.push(';');
+                    out.push_str(param);
+                }
+            }
+        }
+
+        if out.is_empty() {
+            out.push('/');
+        }
+        Cow::Owned(out)
+    }
+}
+
+// ----------------------------------------------------------------
+// Internal helpers
+// ----------------------------------------------------------------
+
+fn is_session_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    DEFAULT_SESSION_PARAMS.contains(&name.as_str())
+        || DEFAULT_SESSION_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// ASP.NET cookieless state such as `(S(abc))` or `(A(x)S(y)F(z))`.
+fn is_cookieless_segment(segment: &str) -> bool {
+    segment.len() > 4
+        && segment.starts_with('(')
+        && segment.ends_with("))")
+        && segment.contains("S(")
+}
+
+/// Decode the escapes of the base64 symbols `+`, `/` and `=`; any
+/// other escape is kept and fails the token alphabet.
+fn decode_base64_escapes(value: &str) -> Cow<'_, [u8]> {
+    if !value.contains('%') {
+        return Cow::Borrowed(value.as_bytes());
+    }
+
+    let mut out = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let symbol = match tail {
+            [hi, lo, ..] if b == b'%' => match [hi.to_ascii_uppercase(), lo.to_ascii_uppercase()] {
+                [b'2', b'B'] => Some(b'+'),
+                [b'2', b'F'] => Some(b'/'),
+                [b'3', b'D'] => Some(b'='),
+                _ => None,
+            },
+            _ => None,
+        };
+        match symbol {
+            Some(symbol) => {
+                out.push(symbol);
+                rest = &tail[2..];
+            }
+            None => {
+                out.push(b);
+                rest = tail;
+            }
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn char_class(b: u8) -> u8 {
+    match b {
+        b'A'..=b'Z' => 0,
+        b'a'..=b'z' => 1,
+        b'0'..=b'9' => 2,
+        _ => 3,
+    }
+}
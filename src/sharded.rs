@@ -0,0 +1,287 @@
+//! Host-sharded deduplication engine.
+//!
+//! A single global filter means one large allocation and, when shared,
+//! one hot lock. [`ShardedDeduplicationEngine`] splits the seen set into
+//! N independent shards and routes each normalized URL to a shard by a
+//! stable hash of its host, so every URL of a host lands in the same
+//! shard. Each shard owns its own [`BloomFilter`], lock and statistics,
+//! which allows:
+//!
+//! - parallel check-and-insert across shards
+//! - sizing capacity per shard (e.g. a large shard for hot host groups)
+//! - persisting, restoring or evicting one shard without touching others
+//!
+//! The sharded engine performs exact-match deduplication only; SimHash
+//! settings in [`Config`] are ignored.
+
+use std::io::{self, Read, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::bloom::BloomFilter;
+use crate::engine::{Config, ConfigError, EngineStatsSnapshot, FilterKind, Stats};
+use crate::hash::hash_bytes;
+use crate::normalizer::UrlNormalizer;
+use crate::persist::PersistError;
+
+/// Deduplication engine partitioned by host.
+///
+/// # Characteristics
+/// - `Send + Sync`; operations take `&self` and lock a single shard
+/// - Shard assignment depends only on host and seed, so it is stable
+///   across processes
+/// - Only [`FilterKind::Bloom`] is supported
+///
+/// # Fields
+/// - `shards`: Independent filters with their own statistics
+/// - `normalizer`: URL normalizer shared by all shards
+/// - `unrouted_checked`: Checked URLs that failed to normalize
+/// - `config`: Configuration the engine was built from
+pub struct ShardedDeduplicationEngine {
+    shards: Vec<Shard>,
+    normalizer: UrlNormalizer,
+    unrouted_checked: AtomicU64,
+    config: Config,
+}
+
+/// One partition of the seen set.
+struct Shard {
+    filter: Mutex<BloomFilter>,
+    capacity: usize,
+    stats: Stats,
+}
+
+impl ShardedDeduplicationEngine {
+    /// Create a sharded engine splitting `config.capacity` evenly.
+    ///
+    /// # Arguments
+    /// - `config`: Engine configuration; `capacity` is the total
+    /// - `num_shards`: Number of shards (N ≥ 1)
+    ///
+    /// Returns [`ConfigError::ZeroShards`] if `num_shards` is 0.
+    pub fn new(config: Config, num_shards: usize) -> Result<Self, ConfigError> {
+        if num_shards == 0 {
+            return Err(ConfigError::ZeroShards);
+        }
+
+        let per_shard = config.capacity.div_ceil(num_shards);
+        Self::with_shard_capacities(config, &vec![per_shard; num_shards])
+    }
+
+    /// Create a sharded engine with an explicit capacity per shard.
+    ///
+    /// `config.capacity` is ignored; the shard count is
+    /// `capacities.len()`, and [`ConfigError::ZeroShards`] is returned
+    /// if `capacities` is empty.
+    pub fn with_shard_capacities(
+        config: Config,
+        capacities: &[usize],
+    ) -> Result<Self, ConfigError> {
+        if capacities.is_empty() {
+            return Err(ConfigError::ZeroShards);
+        }
+        config.validate()?;
+        if config.filter != FilterKind::Bloom {
+            return Err(ConfigError::UnsupportedFilter(config.filter));
+        }
+        if config.verification.is_some() {
+            return Err(ConfigError::UnsupportedVerification);
+        }
+        if capacities.contains(&0) {
+            return Err(ConfigError::ZeroCapacity);
+        }
+
+        let shards = capacities
+            .iter()
+            .map(|&capacity| Shard::new(capacity, &config))
+            .collect();
+
+        Ok(Self {
+            shards,
+            normalizer: UrlNormalizer::with_config(config.normalizer.clone()),
+            unrouted_checked: AtomicU64::new(0),
+            config,
+        })
+    }
+
+    /// Replace the normalizer built from `config.normalizer`.
+    pub fn with_normalizer(mut self, normalizer: UrlNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Normalize, check, and insert a URL into its host's shard.
+    ///
+    /// # Returns
+    /// - `Ok(false)` → URL is new
+    /// - `Ok(true)` → URL is a duplicate
+    ///
+    /// URLs that fail to parse belong to no shard; they count towards
+    /// the engine-wide [`stats`](Self::stats) only.
+    pub fn check_and_insert(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url).inspect_err(|_| {
+            self.unrouted_checked.fetch_add(1, Ordering::Relaxed);
+        })?;
+        let shard = &self.shards[self.shard_index(&normalized)];
+        shard.stats.total_checked.fetch_add(1, Ordering::Relaxed);
+
+        let mut filter = shard.lock();
+        if filter.contains(&normalized) {
+            shard.stats.duplicates_found.fetch_add(1, Ordering::Relaxed);
+            Ok(true)
+        } else {
+            filter.insert(&normalized);
+            shard.stats.urls_inserted.fetch_add(1, Ordering::Relaxed);
+            Ok(false)
+        }
+    }
+
+    /// Check whether a URL is a duplicate without inserting it.
+    pub fn is_duplicate(&self, url: &str) -> Result<bool, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+        let shard = &self.shards[self.shard_index(&normalized)];
+        Ok(shard.lock().contains(&normalized))
+    }
+
+    /// Index of the shard a URL is routed to.
+    pub fn shard_for(&self, url: &str) -> Result<usize, url::ParseError> {
+        let normalized = self.normalizer.normalize(url)?;
+        Ok(self.shard_index(&normalized))
+    }
+
+    /// Number of shards (N).
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Capacity a shard was sized for.
+    pub fn shard_capacity(&self, shard: usize) -> usize {
+        self.shards[shard].capacity
+    }
+
+    /// Statistics of a single shard.
+    pub fn shard_stats(&self, shard: usize) -> EngineStatsSnapshot {
+        self.shards[shard].stats.snapshot()
+    }
+
+    /// Statistics summed over every shard, plus checked URLs that
+    /// failed to normalize.
+    pub fn stats(&self) -> EngineStatsSnapshot {
+        let unrouted = EngineStatsSnapshot {
+            total_checked: self.unrouted_checked.load(Ordering::Relaxed),
+            ..Stats::default().snapshot()
+        };
+        self.shards
+            .iter()
+            .map(|s| s.stats.snapshot())
+            .fold(unrouted, |acc, s| EngineStatsSnapshot {
+                total_checked: acc.total_checked + s.total_checked,
+                duplicates_found: acc.duplicates_found + s.duplicates_found,
+                urls_inserted: acc.urls_inserted + s.urls_inserted,
+                urls_forgotten: acc.urls_forgotten + s.urls_forgotten,
+                false_positives_rejected: acc.false_positives_rejected + s.false_positives_rejected,
+            })
+    }
+
+    /// Estimated current false-positive rate of a shard's filter.
+    pub fn shard_false_positive_rate(&self, shard: usize) -> f64 {
+        self.shards[shard].lock().false_positive_rate()
+    }
+
+    /// Evict a whole shard: every URL routed to it is reported as new
+    /// again, and its statistics restart from zero.
+    pub fn clear_shard(&self, shard: usize) {
+        let shard = &self.shards[shard];
+        let mut filter = shard.lock();
+        *filter = BloomFilter::with_seed(
+            shard.capacity,
+            self.config.false_positive_rate,
+            self.config.seed,
+        );
+        shard.stats.reset();
+    }
+
+    /// Persist one shard's filter in the [`BloomFilter::save`] format.
+    pub fn save_shard<W: Write>(&self, shard: usize, writer: W) -> io::Result<()> {
+        self.shards[shard].lock().save(writer)
+    }
+
+    /// Replace one shard's filter with a previously saved one.
+    ///
+    /// The filter must have been written by [`save_shard`](Self::save_shard)
+    /// for a shard of the same capacity, of an engine with the same seed
+    /// and false-positive rate. The shard's statistics restart from zero.
+    pub fn load_shard<R: Read>(&self, shard: usize, reader: R) -> Result<(), PersistError> {
+        let loaded = BloomFilter::load(reader)?;
+        if loaded.seed() != self.config.seed {
+            return Err(PersistError::Corrupt("shard seed does not match engine"));
+        }
+
+        let shard = &self.shards[shard];
+        let mut filter = shard.lock();
+        if loaded.num_bits() != filter.num_bits() || loaded.num_hashes() != filter.num_hashes() {
+            return Err(PersistError::Corrupt("shard layout does not match engine"));
+        }
+        *filter = loaded;
+        shard.stats.reset();
+        Ok(())
+    }
+
+    /// Normalize a URL with the engine's normalizer.
+    pub fn normalize(&self, url: &str) -> Result<String, url::ParseError> {
+        self.normalizer.normalize(url)
+    }
+
+    /// Configuration the engine was built from.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    /// Route a normalized URL by the stable hash of its host.
+    ///
+    /// Domain rules may produce strings without an authority; those are
+    /// routed by the hash of the whole string.
+    fn shard_index(&self, normalized: &str) -> usize {
+        let key = authority_host(normalized).unwrap_or(normalized);
+
+        (hash_bytes(key.as_bytes(), self.config.seed) % self.shards.len() as u64) as usize
+    }
+}
+
+impl Shard {
+    fn new(capacity: usize, config: &Config) -> Self {
+        Self {
+            filter: Mutex::new(BloomFilter::with_seed(
+                capacity,
+                config.false_positive_rate,
+                config.seed,
+            )),
+            capacity,
+            stats: Stats::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BloomFilter> {
+        self.filter.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Host of a URL string's authority, without userinfo or port.
+///
+/// The string is already normalized, so the host is read directly
+/// instead of parsing the URL a second time.
+fn authority_host(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+
+    let host = match host_port.find(']') {
+        Some(end) if host_port.starts_with('[') => &host_port[..=end],
+        _ => host_port.split(':').next().unwrap_or(host_port),
+    };
+    (!host.is_empty()).then_some(host)
+}
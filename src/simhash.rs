@@ -13,40 +13,59 @@
 //! - ≥ 1M URLs/sec for hash computation
 //! - Tens of millions ops/sec for Hamming distance
 
-use ahash::RandomState;
-use std::hash::{BuildHasher, Hash, Hasher};
+use std::hash::Hasher;
 use url::Url;
 
+use crate::hash::{DEFAULT_SEED, StableHash, StableHasher, hash_bytes};
+
 /// 64-bit SimHash fingerprint.
 ///
 /// Newtype wrapper ensures type safety and makes intent explicit.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct SimHash(pub u64);
 
+impl StableHash for SimHash {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self.0.stable_hash(hasher);
+    }
+}
+
 /// SimHash engine configuration.
 ///
-/// This engine is intentionally minimal: all configuration is fixed
-/// for performance and simplicity.
+/// This engine is intentionally minimal: apart from the hash seed, all
+/// configuration is fixed for performance and simplicity. Engines with
+/// the same seed produce identical fingerprints in every process.
 pub struct SimHashEngine {
-    hasher: RandomState,
+    seed: u64,
     ngram_size: usize,
 }
 
 impl SimHashEngine {
-    /// Create a new 64-bit SimHash engine.
+    /// Create a new 64-bit SimHash engine using [`DEFAULT_SEED`].
     ///
     /// Currently only 64-bit SimHash is supported because it provides
     /// the best tradeoff between speed, memory, and collision resistance
     /// for URL deduplication.
     pub fn new(bit_width: usize) -> Self {
+        Self::with_seed(bit_width, DEFAULT_SEED)
+    }
+
+    /// Create a new 64-bit SimHash engine with an explicit hash seed.
+    pub fn with_seed(bit_width: usize, seed: u64) -> Self {
         assert!(bit_width == 64, "Only 64-bit SimHash is supported");
 
         Self {
-            hasher: RandomState::new(),
+            seed,
             ngram_size: 3,
         }
     }
 
+    /// Seed of the feature hash function.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Compute SimHash directly from a URL string.
     ///
     /// This function performs:
@@ -55,8 +74,19 @@ impl SimHashEngine {
     /// - SimHash accumulation
     ///
     /// All operations are allocation-free after URL parsing.
+    ///
+    /// # Panics
+    /// Panics if `input` is not a valid URL; use
+    /// [`try_compute_hash_from_url`](Self::try_compute_hash_from_url)
+    /// for untrusted input.
     pub fn compute_hash_from_url(&self, input: &str) -> SimHash {
-        let url = Url::parse(input).expect("Invalid URL");
+        self.try_compute_hash_from_url(input).expect("Invalid URL")
+    }
+
+    /// Compute SimHash from a URL string, returning an error for
+    /// unparseable input instead of panicking.
+    pub fn try_compute_hash_from_url(&self, input: &str) -> Result<SimHash, url::ParseError> {
+        let url = Url::parse(input)?;
 
         let mut acc = [0i32; 64];
 
@@ -76,15 +106,16 @@ impl SimHashEngine {
 
         // ---- Query parameters (lowest weight) ----
         for (k, v) in url.query_pairs() {
-            let mut hasher = self.hasher.build_hasher();
-            k.hash(&mut hasher);
-            v.hash(&mut hasher);
+            let mut hasher = StableHasher::with_seed(self.seed);
+            hasher.write(k.as_bytes());
+            hasher.write_u8(0xff);
+            hasher.write(v.as_bytes());
             let h = hasher.finish();
 
             self.accumulate_bits(h, 1, &mut acc);
         }
 
-        SimHash(self.finalize(acc))
+        Ok(SimHash(self.finalize(acc)))
     }
 
     /// Compute similarity score in the range [0.0, 1.0].
@@ -117,7 +148,7 @@ impl SimHashEngine {
 
     #[inline]
     fn apply_feature(&self, bytes: &[u8], weight: i32, acc: &mut [i32; 64]) {
-        let h = self.hasher.hash_one(bytes);
+        let h = hash_bytes(bytes, self.seed);
         self.accumulate_bits(h, weight, acc);
     }
 
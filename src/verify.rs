@@ -0,0 +1,141 @@
+//! Exact verification of membership-filter positives.
+//!
+//! A Bloom filter positive is only "possibly seen": at 1% false
+//! positives on 1B URLs, 10M genuinely new URLs would be dropped. A
+//! [`FingerprintStore`] keeps a wide fingerprint of every recorded URL
+//! so [`DeduplicationEngine`](crate::DeduplicationEngine) can confirm a
+//! positive before declaring a duplicate. Negatives never reach the
+//! store, so it is only consulted on the (rare) filter positives and on
+//! inserts.
+//!
+//! Fingerprints are 128-bit XXH3 hashes; 64-bit stores keep the low
+//! half. With `n` stored URLs, the chance that any new URL collides is
+//! about `n / 2^w` per lookup, i.e. ≈ 5e-11 for `w = 64` at 1B URLs.
+
+use std::collections::HashSet;
+
+use xxhash_rust::xxh3::xxh3_128_with_seed;
+
+/// Fingerprint width kept by a [`MemoryFingerprintStore`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FingerprintWidth {
+    /// 8 bytes per URL.
+    #[default]
+    Bits64,
+    /// 16 bytes per URL; collisions are negligible at any scale.
+    Bits128,
+}
+
+/// Exact set of URL fingerprints.
+///
+/// Implementations may keep fingerprints in memory or on disk; they
+/// may truncate them, which turns a collision into a false positive.
+pub trait FingerprintStore: Send + Sync {
+    /// Record a fingerprint.
+    ///
+    /// Returns `true` if it was not already present.
+    fn insert(&mut self, fingerprint: u128) -> bool;
+
+    /// Whether a fingerprint was recorded.
+    fn contains(&self, fingerprint: u128) -> bool;
+
+    /// Remove a fingerprint.
+    ///
+    /// Returns `true` if it was present.
+    fn remove(&mut self, fingerprint: u128) -> bool;
+
+    /// Number of fingerprints held.
+    fn len(&self) -> usize;
+
+    /// Whether the store holds no fingerprints.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Memory used by the store, in bytes (approximate).
+    fn memory_bytes(&self) -> usize;
+}
+
+/// Compute the 128-bit fingerprint of a normalized URL.
+#[inline]
+pub fn fingerprint(normalized: &str, seed: u64) -> u128 {
+    xxh3_128_with_seed(normalized.as_bytes(), seed)
+}
+
+/// In-memory [`FingerprintStore`] backed by a hash set.
+///
+/// # Characteristics
+/// - No false negatives
+/// - False positives only on fingerprint collisions (see module docs)
+/// - Memory ≈ `width / 8` bytes per URL plus hash-table overhead
+pub struct MemoryFingerprintStore {
+    set: FingerprintSet,
+}
+
+/// Hash set of fingerprints truncated to the configured width.
+enum FingerprintSet {
+    Narrow(HashSet<u64>),
+    Wide(HashSet<u128>),
+}
+
+impl MemoryFingerprintStore {
+    /// Create an empty store keeping fingerprints of the given width.
+    pub fn new(width: FingerprintWidth) -> Self {
+        Self::with_capacity(width, 0)
+    }
+
+    /// Create an empty store with room for `capacity` fingerprints.
+    pub fn with_capacity(width: FingerprintWidth, capacity: usize) -> Self {
+        let set = match width {
+            FingerprintWidth::Bits64 => FingerprintSet::Narrow(HashSet::with_capacity(capacity)),
+            FingerprintWidth::Bits128 => FingerprintSet::Wide(HashSet::with_capacity(capacity)),
+        };
+        Self { set }
+    }
+
+    /// Width of the stored fingerprints.
+    pub fn width(&self) -> FingerprintWidth {
+        match self.set {
+            FingerprintSet::Narrow(_) => FingerprintWidth::Bits64,
+            FingerprintSet::Wide(_) => FingerprintWidth::Bits128,
+        }
+    }
+}
+
+impl FingerprintStore for MemoryFingerprintStore {
+    fn insert(&mut self, fingerprint: u128) -> bool {
+        match &mut self.set {
+            FingerprintSet::Narrow(set) => set.insert(fingerprint as u64),
+            FingerprintSet::Wide(set) => set.insert(fingerprint),
+        }
+    }
+
+    fn contains(&self, fingerprint: u128) -> bool {
+        match &self.set {
+            FingerprintSet::Narrow(set) => set.contains(&(fingerprint as u64)),
+            FingerprintSet::Wide(set) => set.contains(&fingerprint),
+        }
+    }
+
+    fn remove(&mut self, fingerprint: u128) -> bool {
+        match &mut self.set {
+            FingerprintSet::Narrow(set) => set.remove(&(fingerprint as u64)),
+            FingerprintSet::Wide(set) => set.remove(&fingerprint),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match &self.set {
+            FingerprintSet::Narrow(set) => set.len(),
+            FingerprintSet::Wide(set) => set.len(),
+        }
+    }
+
+    fn memory_bytes(&self) -> usize {
+        // One control byte per bucket in addition to the key
+        match &self.set {
+            FingerprintSet::Narrow(set) => set.capacity() * (8 + 1),
+            FingerprintSet::Wide(set) => set.capacity() * (16 + 1),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Sliding-window deduplication with generational Bloom filters.
+//!
+//! A crawler that recrawls pages after a freshness window (e.g. 7 days)
+//! must forget URLs once the window has passed. [`WindowedBloomFilter`]
+//! keeps a ring of `G` Bloom filters ("generations"). New items go into
+//! the newest generation; lookups consult all of them. When the newest
+//! generation has spanned its share of the window, by wall-clock time
+//! or by insert count, the oldest generation is cleared and reused as
+//! the new newest one.
+//!
+//! An item is therefore remembered for between `(G - 1) / G` and the
+//! whole of the window; more generations make the boundary sharper at
+//! the cost of more filters to probe.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::bloom::BloomFilter;
+use crate::hash::DEFAULT_SEED;
+
+/// When the newest generation is retired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// After this many inserts into the newest generation.
+    Inserts(u64),
+    /// After this much time since the newest generation started.
+    Interval(Duration),
+}
+
+/// Ring of Bloom filters that forgets items after a window.
+///
+/// # Characteristics
+/// - No false negatives for items inserted within the last `G - 1`
+///   generations
+/// - Overall false positive rate bounded by `fp_rate` (each generation
+///   is sized for `fp_rate / G`)
+/// - Memory is `G` filters, each sized for `capacity / G` items, or
+///   for the span with [`Rotation::Inserts`]
+///
+/// # Fields
+/// - `generations`: Filters, newest first
+/// - `rotation`: Span of one generation
+/// - `generation_capacity`: Items each generation is sized for
+pub struct WindowedBloomFilter {
+    generations: VecDeque<Generation>,
+    rotation: Rotation,
+    generation_capacity: usize,
+}
+
+/// One filter in the ring and when it became the newest.
+struct Generation {
+    filter: BloomFilter,
+    started: Instant,
+}
+
+impl WindowedBloomFilter {
+    /// Create a windowed filter using [`DEFAULT_SEED`].
+    ///
+    /// # Arguments
+    /// - `capacity`: Expected number of unique items per window; unused
+    ///   with [`Rotation::Inserts`], which fixes each generation's items
+    /// - `fp_rate`: Desired overall false positive probability (p)
+    /// - `generations`: Number of generations in the ring (G ≥ 1)
+    /// - `rotation`: Span of one generation, i.e. `window / G`
+    pub fn new(capacity: usize, fp_rate: f64, generations: usize, rotation: Rotation) -> Self {
+        Self::with_seed(capacity, fp_rate, generations, rotation, DEFAULT_SEED)
+    }
+
+    /// Create a windowed filter with explicit hash seed.
+    pub fn with_seed(
+        capacity: usize,
+        fp_rate: f64,
+        generations: usize,
+        rotation: Rotation,
+        seed: u64,
+    ) -> Self {
+        assert!(generations > 0, "Generation count must be positive");
+        assert!(
+            rotation != Rotation::Inserts(0) && rotation != Rotation::Interval(Duration::ZERO),
+            "Generation span must be positive"
+        );
+
+        // A generation rotates after `span` inserts, so it never holds
+        // more; sizing it from `capacity` instead would saturate it
+        let generation_capacity = match rotation {
+            Rotation::Inserts(span) => usize::try_from(span).unwrap_or(usize::MAX),
+            Rotation::Interval(_) => capacity.div_ceil(generations).max(1),
+        };
+        let generation_fp = fp_rate / generations as f64;
+        let started = Instant::now();
+
+        Self {
+            generations: (0..generations)
+                .map(|_| Generation {
+                    filter: BloomFilter::with_seed(generation_capacity, generation_fp, seed),
+                    started,
+                })
+                .collect(),
+            rotation,
+            generation_capacity,
+        }
+    }
+
+    /// Insert an element into the newest generation.
+    pub fn insert(&mut self, value: &str) {
+        self.insert_at(value, Instant::now());
+    }
+
+    /// Check whether an element was possibly inserted within the window.
+    ///
+    /// Returns:
+    /// - `false` if the element is **definitely not present**
+    /// - `true` if the element is **possibly present**
+    pub fn contains(&self, value: &str) -> bool {
+        self.contains_at(value, Instant::now())
+    }
+
+    /// Check for an element and insert it if it was not seen within the
+    /// window.
+    ///
+    /// A repeat sighting does not refresh the element, so it expires a
+    /// full window after it was first inserted.
+    ///
+    /// # Returns
+    /// - `true` → seen within the window
+    /// - `false` → new (or expired) and now inserted
+    pub fn check_and_insert(&mut self, value: &str) -> bool {
+        self.check_and_insert_at(value, Instant::now())
+    }
+
+    /// [`insert`](Self::insert) with an explicit current time.
+    pub fn insert_at(&mut self, value: &str, now: Instant) {
+        self.tick(now);
+        if let Rotation::Inserts(limit) = self.rotation
+            && self.newest().filter.items_inserted() >= limit
+        {
+            self.rotate(now);
+        }
+
+        self.generations[0].filter.insert(value);
+    }
+
+    /// [`contains`](Self::contains) with an explicit current time.
+    ///
+    /// Generations that have fallen out of the window are ignored even
+    /// if [`tick`](Self::tick) has not retired them yet.
+    pub fn contains_at(&self, value: &str, now: Instant) -> bool {
+        self.live_generations(now)
+            .any(|generation| generation.filter.contains(value))
+    }
+
+    /// [`check_and_insert`](Self::check_and_insert) with an explicit current time.
+    pub fn check_and_insert_at(&mut self, value: &str, now: Instant) -> bool {
+        self.tick(now);
+        if self.contains_at(value, now) {
+            return true;
+        }
+        self.insert_at(value, now);
+        false
+    }
+
+    /// Retire every generation whose span has elapsed by `now`.
+    ///
+    /// Called by every insert; only has an effect with
+    /// [`Rotation::Interval`].
+    pub fn tick(&mut self, now: Instant) {
+        let Rotation::Interval(span) = self.rotation else {
+            return;
+        };
+
+        // Idle for a whole window: everything expired at once
+        let window = span * self.generations.len() as u32;
+        if now.saturating_duration_since(self.newest().started) >= window {
+            for generation in &mut self.generations {
+                generation.filter.clear();
+                generation.started = now;
+            }
+            return;
+        }
+
+        while now.saturating_duration_since(self.newest().started) >= span {
+            let next_start = self.newest().started + span;
+            self.rotate(next_start);
+        }
+    }
+
+    /// Estimate the current overall false positive rate.
+    ///
+    /// Formula:
+    /// `1 - Π(1 - p_i)` over the current rate `p_i` of each generation
+    pub fn false_positive_rate(&self) -> f64 {
+        1.0 - self
+            .generations
+            .iter()
+            .map(|g| 1.0 - g.filter.false_positive_rate())
+            .product::<f64>()
+    }
+
+    /// Number of inserts held across all generations.
+    pub fn items_inserted(&self) -> u64 {
+        self.generations
+            .iter()
+            .map(|g| g.filter.items_inserted())
+            .sum()
+    }
+
+    /// Number of generations (G).
+    pub fn num_generations(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Span of one generation.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Items each generation is sized for.
+    pub fn generation_capacity(&self) -> usize {
+        self.generation_capacity
+    }
+
+    /// Total size of all generation bit arrays, in bits.
+    pub fn num_bits(&self) -> usize {
+        self.generations.iter().map(|g| g.filter.num_bits()).sum()
+    }
+
+    /// Seed of the filter's hash functions.
+    pub fn seed(&self) -> u64 {
+        self.newest().filter.seed()
+    }
+
+    // ----------------------------------------------------------------
+    // Internal helpers
+    // ----------------------------------------------------------------
+
+    #[inline]
+    fn newest(&self) -> &Generation {
+        &self.generations[0]
+    }
+
+    /// Clear the oldest generation and make it the newest.
+    fn rotate(&mut self, started: Instant) {
+        let mut oldest = self
+            .generations
+            .pop_back()
+            .expect("at least one generation");
+        oldest.filter.clear();
+        oldest.started = started;
+        self.generations.push_front(oldest);
+    }
+
+    /// Generations whose span still overlaps the window at `now`.
+    fn live_generations(&self, now: Instant) -> impl Iterator<Item = &Generation> {
+        let window = match self.rotation {
+            Rotation::Interval(span) => Some(span * self.generations.len() as u32),
+            Rotation::Inserts(_) => None,
+        };
+
+        self.generations.iter().filter(move |generation| {
+            window.is_none_or(|w| now.saturating_duration_since(generation.started) < w)
+        })
+    }
+}
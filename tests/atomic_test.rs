@@ -0,0 +1,86 @@
+//! Tests for the lock-free atomic Bloom filter.
+
+use std::sync::Arc;
+use std::thread;
+
+use kaka::atomic::AtomicBloomFilter;
+use kaka::bloom::BloomFilter;
+use proptest::prelude::*;
+
+#[test]
+fn insert_reports_prior_presence() {
+    let bloom = AtomicBloomFilter::new(1000, 0.01);
+
+    assert!(!bloom.insert("https://example.com/a"));
+    assert!(bloom.insert("https://example.com/a"));
+    assert!(bloom.contains("https://example.com/a"));
+    assert!(!bloom.contains("https://example.com/b"));
+    assert_eq!(bloom.items_inserted(), 2);
+}
+
+#[test]
+fn is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AtomicBloomFilter>();
+}
+
+#[test]
+fn concurrent_inserts_have_no_false_negatives() {
+    let bloom = Arc::new(AtomicBloomFilter::new(80_000, 0.01));
+
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let bloom = Arc::clone(&bloom);
+            thread::spawn(move || {
+                for i in 0..10_000 {
+                    bloom.insert(&format!("https://example.com/{}/{}", t, i));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(bloom.items_inserted(), 80_000);
+    for t in 0..8 {
+        for i in 0..10_000 {
+            assert!(bloom.contains(&format!("https://example.com/{}/{}", t, i)));
+        }
+    }
+}
+
+#[test]
+fn bits_match_bloom_filter() {
+    let atomic = AtomicBloomFilter::with_seed(5000, 0.01, 99);
+    let mut plain = BloomFilter::with_seed(5000, 0.01, 99);
+    for i in 0..2000 {
+        let url = format!("https://example.com/{}", i);
+        atomic.insert(&url);
+        plain.insert(&url);
+    }
+
+    let mut saved_atomic = Vec::new();
+    let mut saved_plain = Vec::new();
+    atomic.to_bloom_filter().save(&mut saved_atomic).unwrap();
+    plain.save(&mut saved_plain).unwrap();
+    assert_eq!(saved_atomic, saved_plain);
+
+    let back = AtomicBloomFilter::from(plain);
+    assert_eq!(back.num_bits(), atomic.num_bits());
+    assert!(back.contains("https://example.com/1999"));
+    assert!(back.insert("https://example.com/0"));
+}
+
+proptest! {
+    #[test]
+    fn inserted_items_are_present(items in prop::collection::vec(".*", 1..100)) {
+        let bloom = AtomicBloomFilter::new(1000, 0.01);
+        for item in &items {
+            bloom.insert(item);
+        }
+        for item in &items {
+            prop_assert!(bloom.contains(item));
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Tests for the split-block Bloom filter.
+
+use kaka::blocked::BlockedBloomFilter;
+use kaka::bloom::BloomFilter;
+use proptest::prelude::*;
+
+#[test]
+fn basic_insertion_and_lookup() {
+    let mut bloom = BlockedBloomFilter::new(1000, 0.01);
+
+    for i in 0..1000 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+
+    for i in 0..1000 {
+        assert!(bloom.contains(&format!("https://example.com/{}", i)));
+    }
+    assert_eq!(bloom.items_inserted(), 1000);
+}
+
+#[test]
+fn false_positive_rate_within_expected_bounds() {
+    let mut bloom = BlockedBloomFilter::new(10_000, 0.01);
+
+    for i in 0..10_000 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+
+    let trials = 100_000;
+    let false_positives = (10_000..10_000 + trials)
+        .filter(|i| bloom.contains(&format!("https://example.com/{}", i)))
+        .count();
+
+    let measured_fp = false_positives as f64 / trials as f64;
+    assert!(measured_fp <= 0.012, "measured {}", measured_fp);
+    assert!(bloom.false_positive_rate() <= 0.01);
+}
+
+#[test]
+fn memory_overhead_is_modest() {
+    let blocked = BlockedBloomFilter::new(1_000_000, 0.01);
+    let standard = BloomFilter::new(1_000_000, 0.01);
+
+    assert!(blocked.num_bits() >= standard.num_bits());
+    assert!((blocked.num_bits() as f64) < standard.num_bits() as f64 * 1.5);
+}
+
+#[test]
+fn tiny_and_overloaded_filters() {
+    let mut bloom = BlockedBloomFilter::new(1, 0.01);
+    assert_eq!(bloom.num_blocks(), 1);
+
+    for i in 0..10_000 {
+        bloom.insert(&i.to_string());
+    }
+    assert!(bloom.contains("42"));
+    assert!(bloom.false_positive_rate() > 0.99);
+}
+
+#[test]
+#[should_panic(expected = "False positive rate must be in (0, 1)")]
+fn zero_fp_rate_panics() {
+    BlockedBloomFilter::new(1000, 0.0);
+}
+
+#[test]
+#[should_panic(expected = "False positive rate must be in (0, 1)")]
+fn nan_fp_rate_panics() {
+    BlockedBloomFilter::new(1000, f64::NAN);
+}
+
+#[test]
+#[should_panic(expected = "Blocked filter is limited to 2^32 blocks")]
+fn unreachable_fp_rate_panics() {
+    BlockedBloomFilter::new(1000, 1e-300);
+}
+
+proptest! {
+    #[test]
+    fn no_false_negatives(urls in prop::collection::vec(".*", 1..1000)) {
+        let mut bloom = BlockedBloomFilter::new(1000, 0.01);
+
+        for url in &urls {
+            bloom.insert(url);
+        }
+
+        for url in &urls {
+            prop_assert!(bloom.contains(url), "False negative detected for: {}", url);
+        }
+    }
+}
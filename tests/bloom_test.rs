@@ -1,6 +1,8 @@
 //! Unit and property-based tests for the Bloom filter.
 
-use kaka::bloom::BloomFilter;
+use kaka::bloom::{BloomFilter, MergeError};
+use kaka::persist::PersistError;
+use kaka::simhash::SimHash;
 use proptest::prelude::*;
 
 #[test]
@@ -50,7 +52,296 @@ fn edge_cases_are_handled() {
     assert!(bloom.contains("https://例え.テスト"));
 }
 
+fn populated_filter() -> BloomFilter {
+    let mut bloom = BloomFilter::with_seed(1000, 0.01, 42);
+    for i in 0..500 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+    bloom
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let bloom = populated_filter();
+
+    let mut buf = Vec::new();
+    bloom.save(&mut buf).unwrap();
+    let loaded = BloomFilter::load(buf.as_slice()).unwrap();
+
+    assert_eq!(loaded.num_bits(), bloom.num_bits());
+    assert_eq!(loaded.num_hashes(), bloom.num_hashes());
+    assert_eq!(loaded.seed(), 42);
+    assert_eq!(loaded.items_inserted(), 500);
+    for i in 0..500 {
+        assert!(loaded.contains(&format!("https://example.com/{}", i)));
+    }
+}
+
+#[test]
+fn save_and_load_through_file() {
+    let bloom = populated_filter();
+    let file = tempfile::NamedTempFile::new().unwrap();
+
+    bloom
+        .save(std::io::BufWriter::new(file.reopen().unwrap()))
+        .unwrap();
+    let loaded = BloomFilter::load(std::io::BufReader::new(file.reopen().unwrap())).unwrap();
+
+    assert!(loaded.contains("https://example.com/499"));
+}
+
+#[test]
+fn load_rejects_corruption() {
+    let mut buf = Vec::new();
+    populated_filter().save(&mut buf).unwrap();
+
+    // Flipped payload bit
+    let mut corrupt = buf.clone();
+    let last = corrupt.len() - 1;
+    corrupt[last] ^= 0x01;
+    assert!(matches!(
+        BloomFilter::load(corrupt.as_slice()),
+        Err(PersistError::ChecksumMismatch)
+    ));
+
+    // Tampered item count
+    let mut corrupt = buf.clone();
+    corrupt[32] ^= 0x01;
+    assert!(matches!(
+        BloomFilter::load(corrupt.as_slice()),
+        Err(PersistError::ChecksumMismatch)
+    ));
+
+    // Truncated payload
+    assert!(matches!(
+        BloomFilter::load(&buf[..buf.len() - 8]),
+        Err(PersistError::Io(_))
+    ));
+}
+
+#[test]
+fn load_rejects_foreign_files_and_versions() {
+    let mut buf = Vec::new();
+    populated_filter().save(&mut buf).unwrap();
+
+    let mut wrong_magic = buf.clone();
+    wrong_magic[0] = b'X';
+    assert!(matches!(
+        BloomFilter::load(wrong_magic.as_slice()),
+        Err(PersistError::BadMagic)
+    ));
+
+    let mut wrong_version = buf.clone();
+    wrong_version[4..6].copy_from_slice(&99u16.to_le_bytes());
+    assert!(matches!(
+        BloomFilter::load(wrong_version.as_slice()),
+        Err(PersistError::UnsupportedVersion(99))
+    ));
+}
+
+fn filter_with_range(range: std::ops::Range<usize>) -> BloomFilter {
+    let mut bloom = BloomFilter::with_seed(10_000, 0.01, 3);
+    for i in range {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+    bloom
+}
+
+#[test]
+fn union_contains_both_sets() {
+    let a = filter_with_range(0..3000);
+    let b = filter_with_range(2000..5000);
+
+    let merged = a.union(&b).unwrap();
+    for i in 0..5000 {
+        assert!(merged.contains(&format!("https://example.com/{}", i)));
+    }
+
+    // 5000 distinct items, not the 6000 inserts
+    let n = merged.items_inserted() as f64;
+    assert!((n - 5000.0).abs() < 250.0, "estimated {}", n);
+    assert!(merged.false_positive_rate() < 0.01);
+
+    let mut in_place = a.clone();
+    in_place.union_with(&b).unwrap();
+    assert_eq!(in_place.items_inserted(), merged.items_inserted());
+}
+
+#[test]
+fn intersection_keeps_shared_items() {
+    let a = filter_with_range(0..3000);
+    let b = filter_with_range(2000..5000);
+
+    let common = a.intersect(&b).unwrap();
+    for i in 2000..3000 {
+        assert!(common.contains(&format!("https://example.com/{}", i)));
+    }
+
+    let n = common.items_inserted() as f64;
+    assert!((n - 1000.0).abs() < 250.0, "estimated {}", n);
+
+    // Same estimate as inclusion–exclusion over a materialized union
+    let by_inclusion = a.estimated_cardinality() + b.estimated_cardinality()
+        - a.union(&b).unwrap().estimated_cardinality();
+    assert_eq!(
+        common.items_inserted(),
+        by_inclusion.min(common.estimated_cardinality()).round() as u64
+    );
+
+    let disjoint = filter_with_range(0..1000)
+        .intersect(&filter_with_range(5000..6000))
+        .unwrap();
+    assert!(disjoint.items_inserted() < 100);
+}
+
+#[test]
+fn incompatible_filters_are_rejected() {
+    let a = BloomFilter::with_seed(1000, 0.01, 1);
+
+    assert!(matches!(
+        a.union(&BloomFilter::with_seed(2000, 0.01, 1)),
+        Err(MergeError::BitCountMismatch { .. })
+    ));
+    assert_eq!(
+        a.clone()
+            .intersect_with(&BloomFilter::with_seed(1000, 0.01, 2))
+            .unwrap_err(),
+        MergeError::SeedMismatch { left: 1, right: 2 }
+    );
+}
+
+#[test]
+fn cardinality_ignores_reinserts() {
+    let mut bloom = BloomFilter::new(10_000, 0.01);
+    for _ in 0..3 {
+        for i in 0..2000 {
+            bloom.insert(&format!("https://example.com/{}", i));
+        }
+    }
+
+    assert_eq!(bloom.items_inserted(), 6000);
+    let n = bloom.estimated_cardinality();
+    assert!((n - 2000.0).abs() < 100.0, "estimated {}", n);
+
+    // Counter-based rate overstates, fill-based rate reflects 2000 items
+    assert!(bloom.false_positive_rate() > bloom.false_positive_rate_from_fill());
+}
+
+#[test]
+fn fill_based_rate_matches_measurement() {
+    let bloom = filter_with_range(0..10_000);
+
+    let fp = bloom.false_positive_rate_from_fill();
+    assert!(fp > 0.005 && fp < 0.015, "fp {}", fp);
+    assert!(bloom.fill_ratio() > 0.4 && bloom.fill_ratio() < 0.6);
+}
+
+#[test]
+fn empty_and_full_filters() {
+    let empty = BloomFilter::new(100, 0.01);
+    assert_eq!(empty.estimated_cardinality(), 0.0);
+    assert_eq!(empty.false_positive_rate_from_fill(), 0.0);
+
+    let mut full = BloomFilter::new(10, 0.5);
+    for i in 0..10_000 {
+        full.insert(&i.to_string());
+    }
+    assert!(full.estimated_cardinality().is_infinite());
+    assert_eq!(full.false_positive_rate_from_fill(), 1.0);
+}
+
+#[test]
+fn hashable_values_without_formatting() {
+    let mut bloom = BloomFilter::new(1000, 0.01);
+
+    bloom.insert_hashable(&0xDEAD_BEEF_u64);
+    bloom.insert_hashable(&SimHash(42));
+    bloom.insert_hashable(&("example.com", "/index"));
+    bloom.insert_hashable(b"raw bytes".as_slice());
+
+    assert!(bloom.contains_hashable(&0xDEAD_BEEF_u64));
+    assert!(bloom.contains_hashable(&SimHash(42)));
+    assert!(bloom.contains_hashable(&("example.com", "/index")));
+    assert!(bloom.contains_hashable(b"raw bytes".as_slice()));
+    assert!(!bloom.contains_hashable(&("example.com", "/other")));
+}
+
+#[test]
+fn byte_variants_match_string_variants() {
+    let mut bloom = BloomFilter::new(100, 0.01);
+
+    bloom.insert("https://example.com/");
+    assert!(bloom.contains_bytes(b"https://example.com/"));
+
+    bloom.insert_bytes(b"https://example.org/");
+    assert!(bloom.contains("https://example.org/"));
+}
+
+#[test]
+fn prehashed_items() {
+    let mut bloom = BloomFilter::new(1000, 0.01);
+
+    bloom.insert_prehashed(0x0123_4567_89AB_CDEF, 0xFEDC_BA98_7654_3210);
+    assert!(bloom.contains_prehashed(0x0123_4567_89AB_CDEF, 0xFEDC_BA98_7654_3210));
+    assert!(!bloom.contains_prehashed(1, 2));
+    assert_eq!(bloom.items_inserted(), 1);
+}
+
+#[test]
+fn batch_operations_match_single_item_calls() {
+    let urls: Vec<String> = (0..1000)
+        .map(|i| format!("https://example.com/{}", i))
+        .collect();
+    let refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+
+    let mut single = BloomFilter::new(1000, 0.01);
+    let mut batched = BloomFilter::new(1000, 0.01);
+    for url in &refs[..500] {
+        single.insert(url);
+    }
+    batched.insert_batch(&refs[..500]);
+
+    assert_eq!(single.items_inserted(), batched.items_inserted());
+    let expected: Vec<bool> = refs.iter().map(|u| single.contains(u)).collect();
+    assert_eq!(batched.contains_batch(&refs), expected);
+    assert!(batched.contains_batch(&refs[..500]).iter().all(|&hit| hit));
+}
+
+#[test]
+fn check_and_insert_batch_sees_earlier_items() {
+    let mut bloom = BloomFilter::new(1000, 0.01);
+    bloom.insert("a");
+
+    assert_eq!(
+        bloom.check_and_insert_batch(&["a", "b", "c", "b"]),
+        vec![true, false, false, true]
+    );
+    assert_eq!(bloom.items_inserted(), 3);
+    assert!(bloom.contains_batch(&[]).is_empty());
+}
+
 proptest! {
+    #[test]
+    fn batch_equals_sequential(urls in prop::collection::vec(".*", 0..300)) {
+        let refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+        let mut single = BloomFilter::new(100, 0.05);
+        let mut batched = BloomFilter::new(100, 0.05);
+
+        let expected: Vec<bool> = refs
+            .iter()
+            .map(|url| {
+                let present = single.contains(url);
+                if !present {
+                    single.insert(url);
+                }
+                present
+            })
+            .collect();
+
+        prop_assert_eq!(batched.check_and_insert_batch(&refs), expected);
+        prop_assert_eq!(batched.items_inserted(), single.items_inserted());
+    }
+
     #[test]
     fn no_false_negatives(urls in prop::collection::vec(".*", 1..1000)) {
         let mut bloom = BloomFilter::new(1000, 0.01);
@@ -67,4 +358,17 @@ proptest! {
             );
         }
     }
+
+    #[test]
+    fn no_false_negatives_for_fingerprints(values in prop::collection::vec(any::<u64>(), 1..1000)) {
+        let mut bloom = BloomFilter::new(1000, 0.01);
+
+        for v in &values {
+            bloom.insert_hashable(v);
+        }
+
+        for v in &values {
+            prop_assert!(bloom.contains_hashable(v));
+        }
+    }
 }
@@ -0,0 +1,180 @@
+//! Tests for the thread-safe deduplication engine.
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use kaka::{
+    ConcurrentDeduplicationEngine, Config, ConfigError, FilterKind, FingerprintWidth, UrlNormalizer,
+};
+use rayon::prelude::*;
+
+fn engine() -> ConcurrentDeduplicationEngine {
+    ConcurrentDeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100_000)
+            .with_false_positive_rate(0.001),
+    )
+    .unwrap()
+}
+
+#[test]
+fn is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ConcurrentDeduplicationEngine>();
+}
+
+#[test]
+fn check_and_insert_single_thread() {
+    let engine = engine();
+
+    assert!(!engine.check_and_insert("https://example.com/a").unwrap());
+    assert!(
+        engine
+            .check_and_insert("https://www.example.com/a")
+            .unwrap()
+    );
+    assert!(engine.is_duplicate("https://example.com/a").unwrap());
+    assert!(engine.check_and_insert("not a url").is_err());
+
+    let stats = engine.stats();
+    assert_eq!(stats.total_checked, 3);
+    assert_eq!(stats.urls_inserted, 1);
+    assert_eq!(stats.duplicates_found, 1);
+}
+
+#[test]
+fn exactly_one_thread_sees_new_url() {
+    const THREADS: usize = 16;
+
+    for round in 0..50 {
+        let engine = Arc::new(engine());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let url = format!("https://example.com/race/{}", round);
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let engine = Arc::clone(&engine);
+                let barrier = Arc::clone(&barrier);
+                let url = url.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    engine.check_and_insert(&url).unwrap()
+                })
+            })
+            .collect();
+
+        let new_count = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|&duplicate| !duplicate)
+            .count();
+        assert_eq!(new_count, 1, "round {}", round);
+        assert_eq!(engine.stats().urls_inserted, 1);
+    }
+}
+
+#[test]
+fn shared_across_rayon_workers() {
+    let engine = engine();
+
+    // Every URL is submitted four times from different workers
+    let new_count = (0..40_000)
+        .into_par_iter()
+        .filter(|i| {
+            !engine
+                .check_and_insert(&format!("https://example.com/{}", i % 10_000))
+                .unwrap()
+        })
+        .count();
+
+    assert_eq!(new_count as u64, engine.stats().urls_inserted);
+    assert!(new_count <= 10_000);
+    assert!(
+        new_count >= 9_950,
+        "too many false positives: {}",
+        new_count
+    );
+    for i in 0..10_000 {
+        assert!(
+            engine
+                .is_duplicate(&format!("https://example.com/{}", i))
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn near_duplicates_are_indexed() {
+    let engine = engine();
+
+    engine
+        .check_and_insert("https://example.com/article?id=1")
+        .unwrap();
+    assert!(
+        engine
+            .is_near_duplicate("https://example.com/article?id=1")
+            .unwrap()
+    );
+}
+
+#[test]
+fn near_duplicates_indexed_from_many_threads() {
+    let engine = engine();
+    assert!(engine.config().simhash_enabled);
+
+    (0..8).into_par_iter().for_each(|t| {
+        for i in 0..1_000 {
+            engine
+                .check_and_insert(&format!("https://site{}.example/page/{}", t, i))
+                .unwrap();
+        }
+    });
+
+    assert!(engine.stats().urls_inserted >= 7_990);
+    for t in 0..8 {
+        for i in (0..1_000).step_by(97) {
+            let url = format!("https://site{}.example/page/{}", t, i);
+            assert!(engine.is_near_duplicate(&url).unwrap(), "{} lost", url);
+        }
+    }
+}
+
+#[test]
+fn custom_normalizer_rules_apply() {
+    let mut normalizer = UrlNormalizer::new();
+    normalizer.add_domain_rule("example.com", |url| {
+        format!("https://example.com{}", url.path())
+    });
+    let engine = ConcurrentDeduplicationEngine::new(Config::default())
+        .unwrap()
+        .with_normalizer(normalizer);
+
+    assert!(
+        !engine
+            .check_and_insert("https://example.com/a?x=1")
+            .unwrap()
+    );
+    assert!(
+        engine
+            .check_and_insert("https://example.com/a?x=2")
+            .unwrap()
+    );
+}
+
+#[test]
+fn only_bloom_filter_is_supported() {
+    let result =
+        ConcurrentDeduplicationEngine::new(Config::default().with_filter(FilterKind::Counting));
+    assert!(matches!(
+        result,
+        Err(ConfigError::UnsupportedFilter(FilterKind::Counting))
+    ));
+}
+
+#[test]
+fn exact_verification_is_rejected() {
+    let result = ConcurrentDeduplicationEngine::new(
+        Config::default().with_exact_verification(FingerprintWidth::Bits128),
+    );
+    assert!(matches!(result, Err(ConfigError::UnsupportedVerification)));
+}
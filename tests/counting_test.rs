@@ -0,0 +1,120 @@
+//! Tests for the counting Bloom filter and engine deletion.
+
+use kaka::counting::CountingBloomFilter;
+use kaka::{Config, DeduplicationEngine, EngineError, FilterKind};
+use proptest::prelude::*;
+
+#[test]
+fn insert_contains_remove() {
+    let mut bloom = CountingBloomFilter::new(1000, 0.01);
+
+    bloom.insert("https://example.com/a");
+    bloom.insert("https://example.com/b");
+    assert!(bloom.contains("https://example.com/a"));
+    assert_eq!(bloom.items_inserted(), 2);
+
+    assert!(bloom.remove("https://example.com/a"));
+    assert!(!bloom.contains("https://example.com/a"));
+    assert!(bloom.contains("https://example.com/b"));
+    assert_eq!(bloom.items_inserted(), 1);
+
+    assert!(!bloom.remove("https://example.com/never"));
+}
+
+#[test]
+fn repeated_inserts_need_repeated_removes() {
+    let mut bloom = CountingBloomFilter::new(1000, 0.01);
+
+    bloom.insert("x");
+    bloom.insert("x");
+    assert!(bloom.remove("x"));
+    assert!(bloom.contains("x"));
+    assert!(bloom.remove("x"));
+    assert!(!bloom.contains("x"));
+}
+
+#[test]
+fn overflow_is_detected_and_sticky() {
+    let mut bloom = CountingBloomFilter::new(100, 0.01);
+
+    for _ in 0..20 {
+        bloom.insert("hot");
+    }
+    assert!(bloom.has_overflowed());
+    assert_eq!(bloom.overflow_count(), bloom.num_hashes() as u64);
+
+    // Saturated counters never drop back to zero
+    for _ in 0..20 {
+        bloom.remove("hot");
+    }
+    assert!(bloom.contains("hot"));
+}
+
+#[test]
+fn false_positive_rate_within_expected_bounds() {
+    let mut bloom = CountingBloomFilter::new(10_000, 0.01);
+    for i in 0..10_000 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+
+    let trials = 100_000;
+    let false_positives = (10_000..10_000 + trials)
+        .filter(|i| bloom.contains(&format!("https://example.com/{}", i)))
+        .count();
+
+    assert!((false_positives as f64 / trials as f64) <= 0.011);
+}
+
+#[test]
+fn engine_forgets_urls() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1_000)
+            .with_filter(FilterKind::Counting),
+    )
+    .unwrap();
+
+    let url = "https://example.com/gone";
+    assert!(!engine.check_and_insert(url).unwrap());
+    assert!(engine.check_and_insert(url).unwrap());
+
+    assert!(engine.forget("https://www.example.com/gone#frag").unwrap());
+    assert!(!engine.is_duplicate(url).unwrap());
+    assert!(!engine.check_and_insert(url).unwrap());
+    assert_eq!(engine.stats().urls_forgotten, 1);
+}
+
+#[test]
+fn forget_requires_counting_filter() {
+    let mut engine = DeduplicationEngine::new(Config::default().with_capacity(1_000)).unwrap();
+
+    assert_eq!(
+        engine.forget("https://example.com/"),
+        Err(EngineError::DeletionUnsupported)
+    );
+    assert!(matches!(
+        engine.forget("not a url"),
+        Err(EngineError::InvalidUrl(_))
+    ));
+}
+
+proptest! {
+    #[test]
+    fn removals_never_cause_false_negatives(
+        urls in prop::collection::hash_set("[a-z]{1,12}", 2..300)
+    ) {
+        let urls: Vec<_> = urls.into_iter().collect();
+        let (removed, kept) = urls.split_at(urls.len() / 2);
+        let mut bloom = CountingBloomFilter::new(300, 0.01);
+
+        for url in &urls {
+            bloom.insert(url);
+        }
+        for url in removed {
+            prop_assert!(bloom.remove(url));
+        }
+        for url in kept {
+            prop_assert!(bloom.contains(url));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Tests for the cuckoo filter and its engine backend.
+
+use kaka::cuckoo::CuckooFilter;
+use kaka::{Config, DeduplicationEngine, EngineError, FilterKind};
+use proptest::prelude::*;
+
+#[test]
+fn insert_contains_remove() {
+    let mut filter = CuckooFilter::new(1000);
+
+    assert!(filter.insert("https://example.com/a"));
+    assert!(filter.insert("https://example.com/b"));
+    assert!(filter.contains("https://example.com/a"));
+    assert_eq!(filter.len(), 2);
+
+    assert!(filter.remove("https://example.com/a"));
+    assert!(!filter.contains("https://example.com/a"));
+    assert!(filter.contains("https://example.com/b"));
+    assert_eq!(filter.len(), 1);
+
+    assert!(!filter.remove("https://example.com/never"));
+}
+
+#[test]
+fn duplicate_inserts_need_duplicate_removes() {
+    let mut filter = CuckooFilter::new(100);
+
+    filter.insert("x");
+    filter.insert("x");
+    assert!(filter.remove("x"));
+    assert!(filter.contains("x"));
+    assert!(filter.remove("x"));
+    assert!(!filter.contains("x"));
+}
+
+#[test]
+fn load_factor_reaches_target() {
+    let mut filter = CuckooFilter::new(10_000);
+
+    for i in 0..10_000 {
+        assert!(filter.insert(&format!("https://example.com/{}", i)));
+    }
+    assert!(filter.load_factor() > 0.5 && filter.load_factor() <= 1.0);
+    for i in 0..10_000 {
+        assert!(filter.contains(&format!("https://example.com/{}", i)));
+    }
+}
+
+#[test]
+fn table_is_sized_for_target_load() {
+    for capacity in [1000, 100_000, 1_000_000] {
+        let filter = CuckooFilter::new(capacity);
+        let bits_per_item = filter.num_slots() as f64 * 16.0 / capacity as f64;
+        assert!(
+            bits_per_item < 17.5,
+            "{} bits per item at capacity {}",
+            bits_per_item,
+            capacity
+        );
+    }
+
+    let mut filter = CuckooFilter::new(100_000);
+    for i in 0..100_000 {
+        assert!(filter.insert(&format!("https://example.com/{}", i)));
+    }
+    assert!(filter.load_factor() > 0.9, "load {}", filter.load_factor());
+}
+
+#[test]
+fn false_positive_rate_is_low() {
+    let mut filter = CuckooFilter::new(50_000);
+    for i in 0..50_000 {
+        filter.insert(&format!("https://example.com/{}", i));
+    }
+
+    let false_positives = (50_000..150_000)
+        .filter(|i| filter.contains(&format!("https://example.com/{}", i)))
+        .count();
+    let rate = false_positives as f64 / 100_000.0;
+    assert!(rate < 0.001, "FP rate {} too high", rate);
+    assert!(filter.false_positive_rate() < 0.001);
+}
+
+#[test]
+fn full_filter_rejects_inserts_without_losing_items() {
+    let mut filter = CuckooFilter::new(100);
+    let capacity = filter.num_slots();
+
+    let stored: Vec<String> = (0..capacity * 2)
+        .map(|i| format!("https://example.com/{}", i))
+        .take_while(|url| filter.insert(url))
+        .collect();
+
+    assert!(filter.is_full());
+    assert!(stored.len() <= capacity + 1);
+    assert!(!filter.insert("https://example.com/rejected"));
+    for url in &stored {
+        assert!(filter.contains(url), "lost {}", url);
+    }
+
+    // Freeing space accepts inserts again
+    for url in &stored[..10] {
+        assert!(filter.remove(url));
+    }
+    assert!(!filter.is_full());
+    assert!(filter.insert("https://example.com/after"));
+}
+
+#[test]
+fn engine_uses_cuckoo_filter() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1000)
+            .with_filter(FilterKind::Cuckoo),
+    )
+    .unwrap();
+    let url = "https://example.com/page";
+
+    assert!(!engine.check_and_insert(url).unwrap());
+    assert!(engine.check_and_insert(url).unwrap());
+    assert_eq!(engine.forget(url), Ok(true));
+    assert!(!engine.check_and_insert(url).unwrap());
+}
+
+#[test]
+fn engine_reports_full_cuckoo_filter() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(10)
+            .with_filter(FilterKind::Cuckoo)
+            .disable_simhash(),
+    )
+    .unwrap();
+
+    let result = (0..1000)
+        .map(|i| engine.check_and_insert(&format!("https://example.com/{}", i)))
+        .find(Result::is_err);
+    assert_eq!(result, Some(Err(EngineError::FilterFull)));
+}
+
+proptest! {
+    // Distinct items: one key fits at most 2b copies of its fingerprint
+    #[test]
+    fn inserted_items_are_present(items in prop::collection::hash_set(".*", 1..100)) {
+        let mut filter = CuckooFilter::new(1000);
+        for item in &items {
+            prop_assert!(filter.insert(item));
+        }
+        for item in &items {
+            prop_assert!(filter.contains(item));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Tests for the membership traits and pluggable engine backends.
+
+use std::collections::HashSet;
+
+use kaka::filter::{FilterFull, MembershipFilter, MembershipQuery};
+use kaka::{
+    AtomicBloomFilter, BlockedBloomFilter, BloomFilter, Config, CountingBloomFilter, CuckooFilter,
+    DeduplicationEngine, EngineError, ScalableBloomFilter,
+};
+
+/// Exact in-memory store, standing in for a caller's own backend.
+#[derive(Default)]
+struct ExactSet {
+    items: HashSet<String>,
+    limit: Option<usize>,
+}
+
+impl MembershipQuery for ExactSet {
+    fn contains(&self, item: &str) -> bool {
+        self.items.contains(item)
+    }
+
+    fn len_estimate(&self) -> u64 {
+        self.items.len() as u64
+    }
+
+    fn fp_rate(&self) -> f64 {
+        0.0
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.items.iter().map(String::capacity).sum()
+    }
+}
+
+impl MembershipFilter for ExactSet {
+    fn insert(&mut self, item: &str) -> Result<(), FilterFull> {
+        if self.limit.is_some_and(|limit| self.items.len() >= limit) {
+            return Err(FilterFull);
+        }
+        self.items.insert(item.to_string());
+        Ok(())
+    }
+
+    fn supports_removal(&self) -> bool {
+        true
+    }
+
+    fn remove(&mut self, item: &str) -> Option<bool> {
+        Some(self.items.remove(item))
+    }
+}
+
+fn builtin_filters() -> Vec<Box<dyn MembershipFilter>> {
+    vec![
+        Box::new(BloomFilter::new(1000, 0.01)),
+        Box::new(BlockedBloomFilter::new(1000, 0.01)),
+        Box::new(AtomicBloomFilter::new(1000, 0.01)),
+        Box::new(ScalableBloomFilter::new(100, 0.01)),
+        Box::new(CountingBloomFilter::new(1000, 0.01)),
+        Box::new(CuckooFilter::new(1000)),
+    ]
+}
+
+#[test]
+fn builtin_filters_share_the_contract() {
+    for mut filter in builtin_filters() {
+        assert_eq!(filter.check_and_insert("https://example.com/a"), Ok(false));
+        assert_eq!(filter.check_and_insert("https://example.com/a"), Ok(true));
+        filter.insert("https://example.com/b").unwrap();
+
+        assert!(filter.contains("https://example.com/b"));
+        assert!(!filter.contains("https://example.com/c"));
+        assert_eq!(filter.len_estimate(), 2);
+        assert!((0.0..0.01).contains(&filter.fp_rate()));
+        assert!(filter.memory_bytes() > 0);
+    }
+}
+
+#[test]
+fn removal_support_is_reported() {
+    let supports_removal: Vec<bool> = builtin_filters()
+        .into_iter()
+        .map(|mut f| {
+            f.insert("x").unwrap();
+            assert_eq!(f.remove("x").is_some(), f.supports_removal());
+            f.supports_removal()
+        })
+        .collect();
+
+    assert_eq!(supports_removal, [false, false, false, false, true, true]);
+}
+
+#[test]
+fn memory_matches_filter_size() {
+    let bloom = BloomFilter::new(10_000, 0.01);
+    assert_eq!(
+        MembershipQuery::memory_bytes(&bloom),
+        bloom.num_bits().div_ceil(64) * 8
+    );
+
+    let counting = CountingBloomFilter::new(10_000, 0.01);
+    assert_eq!(
+        MembershipQuery::memory_bytes(&counting),
+        counting.num_counters().div_ceil(2)
+    );
+}
+
+#[test]
+fn engine_accepts_custom_filter() {
+    let mut engine =
+        DeduplicationEngine::with_filter(Config::default(), ExactSet::default()).unwrap();
+
+    assert!(!engine.check_and_insert("https://example.com/a").unwrap());
+    assert!(
+        engine
+            .check_and_insert("https://www.example.com/a")
+            .unwrap()
+    );
+    assert_eq!(engine.false_positive_rate(), 0.0);
+    assert!(engine.filter_memory_bytes() > 0);
+    assert_eq!(engine.forget("https://example.com/a"), Ok(true));
+    assert!(!engine.is_duplicate("https://example.com/a").unwrap());
+}
+
+#[test]
+fn engine_surfaces_full_custom_filter() {
+    let filter = ExactSet {
+        limit: Some(1),
+        ..ExactSet::default()
+    };
+    let mut engine = DeduplicationEngine::with_filter(Config::default(), filter).unwrap();
+
+    engine.check_and_insert("https://example.com/a").unwrap();
+    assert_eq!(
+        engine.check_and_insert("https://example.com/b"),
+        Err(EngineError::FilterFull)
+    );
+}
+
+#[test]
+fn custom_filter_config_is_still_validated() {
+    let result =
+        DeduplicationEngine::with_filter(Config::default().with_threads(0), ExactSet::default());
+    assert!(result.is_err());
+}
@@ -0,0 +1,215 @@
+//! Tests for the binary fuse backed frozen URL set.
+
+use kaka::frozen::FrozenUrlSet;
+use kaka::persist::PersistError;
+use kaka::{BloomFilter, MembershipQuery, UrlNormalizer};
+use proptest::prelude::*;
+
+fn urls(range: std::ops::Range<usize>) -> Vec<String> {
+    range
+        .map(|i| format!("https://example.com/page/{}", i))
+        .collect()
+}
+
+#[test]
+fn built_urls_are_present() {
+    let set = FrozenUrlSet::build(urls(0..10_000));
+
+    assert_eq!(set.len(), 10_000);
+    for url in urls(0..10_000) {
+        assert!(set.contains_url(&url).unwrap());
+    }
+}
+
+#[test]
+fn lookups_are_normalized() {
+    let set = FrozenUrlSet::build(["https://www.Example.com/a?utm_source=x&b=2&a=1#top"]);
+
+    assert!(set.contains_url("https://example.com/a?a=1&b=2").unwrap());
+    assert!(set.contains("https://example.com/a?a=1&b=2"));
+    assert!(!set.contains_url("https://example.com/b").unwrap());
+}
+
+#[test]
+fn invalid_and_duplicate_urls_are_skipped() {
+    let set = FrozenUrlSet::build([
+        "https://example.com/a",
+        "not a url",
+        "https://www.example.com/a",
+        "https://example.com/b",
+    ]);
+
+    assert_eq!(set.len(), 2);
+    assert!(set.contains_url("https://example.com/b").unwrap());
+}
+
+#[test]
+fn empty_and_tiny_sets() {
+    let empty = FrozenUrlSet::build(Vec::<String>::new());
+    assert!(empty.is_empty());
+    assert!(!empty.contains_url("https://example.com/").unwrap());
+
+    for n in 1..20 {
+        let set = FrozenUrlSet::build(urls(0..n));
+        for url in urls(0..n) {
+            assert!(set.contains_url(&url).unwrap());
+        }
+    }
+}
+
+#[test]
+fn false_positive_rate_and_size() {
+    let set = FrozenUrlSet::build(urls(0..100_000));
+
+    let false_positives = urls(100_000..300_000)
+        .iter()
+        .filter(|url| set.contains_url(url).unwrap())
+        .count();
+    let rate = false_positives as f64 / 200_000.0;
+    assert!(rate < 0.006, "FP rate {} too high", rate);
+    assert!(
+        set.bits_per_key() < 10.0,
+        "{} bits per key",
+        set.bits_per_key()
+    );
+}
+
+#[test]
+fn save_load_roundtrip() {
+    let set = FrozenUrlSet::build(urls(0..5_000));
+    let mut buf = Vec::new();
+    set.save(&mut buf).unwrap();
+
+    let loaded = FrozenUrlSet::load(buf.as_slice()).unwrap();
+    assert_eq!(loaded.len(), set.len());
+    assert_eq!(loaded.memory_bytes(), set.memory_bytes());
+    for url in urls(0..5_000) {
+        assert!(loaded.contains_url(&url).unwrap());
+    }
+}
+
+#[test]
+fn load_rejects_corruption() {
+    let set = FrozenUrlSet::build(urls(0..100));
+    let mut buf = Vec::new();
+    set.save(&mut buf).unwrap();
+
+    let mut flipped = buf.clone();
+    let last = flipped.len() - 1;
+    flipped[last] ^= 0xff;
+    assert!(matches!(
+        FrozenUrlSet::load(flipped.as_slice()),
+        Err(PersistError::ChecksumMismatch)
+    ));
+
+    let mut bad_magic = buf.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        FrozenUrlSet::load(bad_magic.as_slice()),
+        Err(PersistError::BadMagic)
+    ));
+
+    assert!(matches!(
+        FrozenUrlSet::load(&buf[..buf.len() - 1]),
+        Err(PersistError::Io(_))
+    ));
+}
+
+/// Rewrite header fields of a saved set and recompute its checksum.
+fn patched(buf: &[u8], offset: usize, value: &[u8]) -> Vec<u8> {
+    let mut out = buf.to_vec();
+    out[offset..offset + value.len()].copy_from_slice(value);
+
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    hasher.update(&out[..48]);
+    hasher.update(&out[56..]);
+    out[48..56].copy_from_slice(&hasher.digest().to_le_bytes());
+    out
+}
+
+#[test]
+fn load_rejects_bad_layout_with_valid_checksum() {
+    let set = FrozenUrlSet::build(urls(0..100));
+    let mut buf = Vec::new();
+    set.save(&mut buf).unwrap();
+    assert!(FrozenUrlSet::load(patched(&buf, 0, b"KFUS").as_slice()).is_ok());
+
+    // No segment of starting positions: lookups would index out of bounds
+    let segment_length = 4u32;
+    let mut layout = Vec::new();
+    layout.extend_from_slice(&segment_length.to_le_bytes());
+    layout.extend_from_slice(&0u32.to_le_bytes());
+    layout.extend_from_slice(&(2 * segment_length as u64).to_le_bytes());
+    layout.extend_from_slice(&0u64.to_le_bytes());
+    let empty_segments = patched(&buf[..56 + 2 * segment_length as usize], 24, &layout);
+    assert!(matches!(
+        FrozenUrlSet::load(empty_segments.as_slice()),
+        Err(PersistError::Corrupt(_))
+    ));
+
+    // More items than table slots
+    assert!(matches!(
+        FrozenUrlSet::load(patched(&buf, 40, &u64::MAX.to_le_bytes()).as_slice()),
+        Err(PersistError::Corrupt(_))
+    ));
+}
+
+#[test]
+fn custom_normalizer_is_used() {
+    let mut normalizer = UrlNormalizer::new();
+    normalizer.add_tracking_param("session");
+    let set = FrozenUrlSet::build_with(
+        ["https://example.com/a?session=1"],
+        normalizer,
+        kaka::hash::DEFAULT_SEED,
+    );
+
+    assert!(set.contains_url("https://example.com/a").unwrap());
+}
+
+#[test]
+fn queried_like_a_bloom_filter() {
+    let normalizer = UrlNormalizer::new();
+    let normalized: Vec<String> = urls(0..1_000)
+        .iter()
+        .map(|url| normalizer.normalize(url).unwrap())
+        .collect();
+
+    let mut bloom = BloomFilter::new(1_000, 0.01);
+    for url in &normalized {
+        bloom.insert(url);
+    }
+    let stores: [Box<dyn MembershipQuery>; 2] = [
+        Box::new(FrozenUrlSet::build(urls(0..1_000))),
+        Box::new(bloom),
+    ];
+
+    for store in &stores {
+        assert!(normalized.iter().all(|url| store.contains(url)));
+        assert_eq!(store.len_estimate(), 1_000);
+    }
+}
+
+#[test]
+fn membership_trait_reports_size() {
+    let set = FrozenUrlSet::build(urls(0..1_000));
+
+    assert_eq!(set.len_estimate(), 1_000);
+    assert_eq!(MembershipQuery::memory_bytes(&set), set.memory_bytes());
+    assert!((set.fp_rate() - 1.0 / 256.0).abs() < 1e-12);
+}
+
+proptest! {
+    #[test]
+    fn no_false_negatives(paths in prop::collection::vec("[a-z0-9/]{0,20}", 0..200)) {
+        let urls: Vec<String> = paths
+            .iter()
+            .map(|p| format!("https://example.com/{}", p))
+            .collect();
+        let set = FrozenUrlSet::build(&urls);
+
+        for url in &urls {
+            prop_assert!(set.contains_url(url).unwrap());
+        }
+    }
+}
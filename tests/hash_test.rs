@@ -0,0 +1,114 @@
+//! Stability tests for seeded hashing.
+//!
+//! The golden values below pin the hash algorithm: if any of them
+//! change, persisted filters and shared fingerprints from earlier
+//! versions become meaningless.
+
+use std::hash::{Hash, Hasher};
+
+use kaka::bloom::BloomFilter;
+use kaka::hash::{DEFAULT_SEED, StableHash, StableHasher, StableState, hash_bytes};
+use kaka::simhash::SimHashEngine;
+
+#[test]
+fn xxh3_reference_vector() {
+    // XXH3-64 of the empty input with seed 0, from the reference implementation
+    assert_eq!(hash_bytes(b"", 0), 0x2d06_8005_38d3_94c2);
+}
+
+#[test]
+fn golden_hashes_are_stable() {
+    assert_eq!(
+        hash_bytes(b"https://example.com/", DEFAULT_SEED),
+        0xc6bf_84b1_23cd_f81b
+    );
+    assert_eq!(
+        hash_bytes(b"https://example.com/", 0),
+        0x4ca4_ca39_4042_cceb
+    );
+
+    let mut hasher = StableHasher::with_seed(DEFAULT_SEED);
+    42u64.hash(&mut hasher);
+    assert_eq!(hasher.finish(), 0x930d_57b2_0770_9ad3);
+}
+
+fn stable_hash<T: StableHash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = StableHasher::with_seed(DEFAULT_SEED);
+    value.stable_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn golden_stable_encodings() {
+    assert_eq!(stable_hash(&42u64), 0x930d_57b2_0770_9ad3);
+    assert_eq!(stable_hash("example.com"), 0xef3f_1e80_1bbd_574e);
+    assert_eq!(
+        stable_hash(&"example.com".to_string()),
+        stable_hash("example.com")
+    );
+    assert_eq!(stable_hash(b"raw bytes".as_slice()), 0xa415_410f_feeb_dd01);
+    assert_eq!(
+        stable_hash(b"raw bytes"),
+        stable_hash(b"raw bytes".as_slice())
+    );
+    assert_eq!(
+        stable_hash(&("example.com", "/index")),
+        0x6315_260e_fb4f_c51c
+    );
+
+    // Length prefixes and terminators keep field boundaries apart
+    assert_ne!(stable_hash(&("ab", "c")), stable_hash(&("a", "bc")));
+    assert_ne!(
+        stable_hash(&(vec![1u8], vec![2u8])),
+        stable_hash(&(vec![1u8, 2], Vec::<u8>::new()))
+    );
+}
+
+#[test]
+fn golden_simhash_is_stable() {
+    let url = "https://example.com/article?id=1";
+
+    assert_eq!(
+        SimHashEngine::new(64).compute_hash_from_url(url).0,
+        0xc838_0046_2d1a_d48c
+    );
+    assert_eq!(
+        SimHashEngine::with_seed(64, 7).compute_hash_from_url(url).0,
+        0xa64a_6e77_f1e7_819f
+    );
+}
+
+#[test]
+fn independent_engines_agree() {
+    let a = SimHashEngine::with_seed(64, 1234);
+    let b = SimHashEngine::with_seed(64, 1234);
+    let url = "https://example.com/some/path?q=rust";
+
+    assert_eq!(a.compute_hash_from_url(url), b.compute_hash_from_url(url));
+}
+
+#[test]
+fn seed_changes_hash_functions() {
+    let a = SimHashEngine::with_seed(64, 1);
+    let b = SimHashEngine::with_seed(64, 2);
+    let url = "https://example.com/some/path";
+
+    assert_ne!(a.compute_hash_from_url(url), b.compute_hash_from_url(url));
+    assert_ne!(hash_bytes(b"x", 1), hash_bytes(b"x", 2));
+}
+
+#[test]
+fn bloom_seed_is_recorded() {
+    assert_eq!(BloomFilter::new(10, 0.01).seed(), DEFAULT_SEED);
+    assert_eq!(BloomFilter::with_seed(10, 0.01, 99).seed(), 99);
+}
+
+#[test]
+fn stable_state_builds_seeded_hashers() {
+    use std::hash::BuildHasher;
+
+    let state = StableState::with_seed(5);
+    assert_eq!(state.seed(), 5);
+    assert_eq!(state.hash_one(7u32), state.hash_one(7u32));
+    assert_ne!(state.hash_one(7u32), StableState::default().hash_one(7u32));
+}
@@ -1,8 +1,13 @@
-use kaka::DeduplicationEngine;
+use kaka::{Config, ConfigError, DeduplicationEngine};
 
 #[test]
 fn duplicate_detection_accuracy() {
-    let mut engine = DeduplicationEngine::new(10_000, 0.01);
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(10_000)
+            .with_false_positive_rate(0.01),
+    )
+    .unwrap();
     let mut accepted = 0;
 
     for i in 0..10_000 {
@@ -19,7 +24,12 @@ fn duplicate_detection_accuracy() {
 
 #[test]
 fn normalization_effectiveness() {
-    let mut engine = DeduplicationEngine::new(1_000, 0.01);
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1_000)
+            .with_false_positive_rate(0.01),
+    )
+    .unwrap();
 
     let first = "http://example.com?a=1&b=2";
     let second = "https://example.com?b=2&a=1";
@@ -33,7 +43,12 @@ fn normalization_effectiveness() {
 
 #[test]
 fn mixed_workload_stats() {
-    let mut engine = DeduplicationEngine::new(1_000, 0.01);
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1_000)
+            .with_false_positive_rate(0.01),
+    )
+    .unwrap();
     let mut injected_duplicates = 0;
 
     for i in 0..1_000 {
@@ -52,10 +67,108 @@ fn mixed_workload_stats() {
     assert!(stats.duplicates_found >= injected_duplicates as u64);
 }
 
+#[test]
+fn engine_normalizes_before_checking() {
+    let mut engine = DeduplicationEngine::new(Config::default().with_capacity(1_000)).unwrap();
+
+    assert_eq!(
+        engine
+            .normalize("HTTPS://WWW.Example.com/page?utm_source=x")
+            .unwrap(),
+        "https://example.com/page"
+    );
+
+    assert!(!engine.check_and_insert("https://example.com/page").unwrap());
+    assert!(
+        engine
+            .is_duplicate("https://www.example.com/page?utm_source=x")
+            .unwrap()
+    );
+}
+
+#[test]
+fn near_duplicates_follow_simhash_switch() {
+    let mut enabled = DeduplicationEngine::new(Config::default().with_capacity(1_000)).unwrap();
+    enabled
+        .check_and_insert("https://example.com/article")
+        .unwrap();
+    assert!(
+        enabled
+            .is_near_duplicate("https://example.com/article?id=1")
+            .unwrap()
+    );
+
+    let mut disabled =
+        DeduplicationEngine::new(Config::default().with_capacity(1_000).disable_simhash()).unwrap();
+    disabled
+        .check_and_insert("https://example.com/article")
+        .unwrap();
+    assert!(
+        !disabled
+            .is_near_duplicate("https://example.com/article?id=1")
+            .unwrap()
+    );
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    let engine = |config: Config| DeduplicationEngine::new(config).err();
+
+    assert_eq!(
+        engine(Config::default().with_capacity(0)),
+        Some(ConfigError::ZeroCapacity)
+    );
+    assert_eq!(
+        engine(Config::default().with_false_positive_rate(1.5)),
+        Some(ConfigError::InvalidFalsePositiveRate(1.5))
+    );
+    assert_eq!(
+        engine(Config::default().with_similarity_threshold(0.0)),
+        Some(ConfigError::InvalidSimilarityThreshold(0.0))
+    );
+    assert_eq!(
+        engine(Config::default().with_threads(0)),
+        Some(ConfigError::ZeroThreads)
+    );
+}
+
+#[test]
+fn batch_matches_single_url_calls() {
+    let config = Config::default()
+        .with_capacity(5_000)
+        .with_false_positive_rate(0.01);
+    let mut single = DeduplicationEngine::new(config.clone().with_threads(1)).unwrap();
+    let mut batched = DeduplicationEngine::new(config.with_threads(4)).unwrap();
+
+    // Large enough to be normalized in parallel, with repeats and
+    // invalid URLs mixed in
+    let urls: Vec<String> = (0..3_000)
+        .map(|i| match i % 7 {
+            0 => "not a url".to_string(),
+            1 => format!("HTTPS://Example.com/item{}", i / 2),
+            _ => format!("https://example.com/item{}", i / 2),
+        })
+        .collect();
+    let refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+
+    let expected: Vec<_> = refs.iter().map(|u| single.check_and_insert(u)).collect();
+    assert_eq!(batched.check_and_insert_batch(&refs), expected);
+
+    let (a, b) = (single.stats(), batched.stats());
+    assert_eq!(a.total_checked, b.total_checked);
+    assert_eq!(a.duplicates_found, b.duplicates_found);
+    assert_eq!(a.urls_inserted, b.urls_inserted);
+}
+
 #[test]
 #[ignore] // Performance tests must never run in CI or default `cargo test`
 fn performance_under_load() {
-    let mut engine = DeduplicationEngine::new(100_000, 0.01);
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100_000)
+            .with_false_positive_rate(0.01),
+    )
+    .unwrap();
 
     let start = std::time::Instant::now();
 
@@ -0,0 +1,154 @@
+//! Tests for the LSHBloom near-duplicate index.
+
+use kaka::lshbloom::{AtomicLSHBloom, LSHBloom};
+use kaka::simhash::{SimHash, SimHashEngine};
+use proptest::prelude::*;
+
+#[test]
+fn identical_signature_is_similar() {
+    let mut lsh = LSHBloom::new(200, 0.01, 8);
+    let hash = SimHash(0xDEAD_BEEF_CAFE_BABE);
+
+    assert!(!lsh.query_similar(hash));
+    lsh.insert(hash);
+
+    assert!(lsh.query_similar(hash));
+    assert_eq!(lsh.matching_bands(hash), 8);
+}
+
+#[test]
+fn single_bit_difference_matches_remaining_bands() {
+    let mut lsh = LSHBloom::new(200, 0.01, 8);
+    let hash = SimHash(0x0123_4567_89AB_CDEF);
+    lsh.insert(hash);
+
+    // Flipping one bit breaks exactly one band
+    let near = SimHash(hash.0 ^ 1);
+    assert!(lsh.query_similar(near));
+    assert!(lsh.matching_bands(near) >= 7);
+}
+
+#[test]
+fn unrelated_signatures_are_rarely_similar() {
+    let mut lsh = LSHBloom::new(10_000, 0.01, 2);
+
+    for i in 0..10_000u64 {
+        lsh.insert(SimHash(i.wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+    }
+
+    let trials = 10_000u64;
+    let mut false_positives = 0;
+    for i in 0..trials {
+        let probe = SimHash((i + 1_000_000).wrapping_mul(0xC2B2_AE3D_27D4_EB4F));
+        if lsh.query_similar(probe) {
+            false_positives += 1;
+        }
+    }
+
+    assert!((false_positives as f64 / trials as f64) < 0.02);
+}
+
+#[test]
+fn check_and_insert_reports_previous_state() {
+    let mut lsh = LSHBloom::new(16, 0.01, 16);
+    let hash = SimHash(42);
+
+    assert!(!lsh.check_and_insert(hash));
+    assert!(lsh.check_and_insert(hash));
+    assert_eq!(lsh.len(), 2);
+}
+
+#[test]
+fn similar_urls_are_detected() {
+    let engine = SimHashEngine::new(64);
+    let mut lsh = LSHBloom::new(16, 0.01, 16);
+
+    lsh.insert(engine.compute_hash_from_url("https://example.com/article"));
+
+    let near = engine.compute_hash_from_url("https://example.com/article?id=1");
+    assert!(lsh.query_similar(near));
+}
+
+#[test]
+fn band_geometry_and_threshold() {
+    let lsh = LSHBloom::new(16, 0.01, 16);
+
+    assert_eq!(lsh.num_bands(), 16);
+    assert_eq!(lsh.rows_per_band(), 4);
+    assert!(lsh.candidate_probability(0.9) > lsh.candidate_probability(0.5));
+    assert!((lsh.threshold() - 0.5).abs() < 1e-9);
+
+    assert_eq!(LSHBloom::bands_for_threshold(0.5), 16);
+    assert_eq!(LSHBloom::bands_for_threshold(0.92), 4);
+}
+
+#[test]
+fn engine_band_count_keeps_unrelated_signatures_apart() {
+    // The engine's defaults: threshold 0.9 at a million URLs
+    let bands = LSHBloom::bands_for_capacity(0.9, 1_000_000, 0.01);
+    assert_eq!(bands, 2);
+    assert_eq!(
+        LSHBloom::bands_for_capacity(0.92, 100, 0.01),
+        LSHBloom::bands_for_threshold(0.92)
+    );
+
+    let mut lsh = LSHBloom::new(1_000_000, 0.01, bands);
+    for i in 0..300_000u64 {
+        lsh.insert(SimHash(i.wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+    }
+
+    let trials = 10_000u64;
+    let false_positives = (0..trials)
+        .filter(|i| lsh.query_similar(SimHash((i + 1_000_000).wrapping_mul(0xC2B2_AE3D_27D4_EB4F))))
+        .count();
+    assert!(
+        (false_positives as f64 / trials as f64) < 0.01,
+        "{} false positives",
+        false_positives
+    );
+}
+
+#[test]
+#[should_panic(expected = "Capacity exceeds the 65536 values of a 16-row band")]
+fn capacity_beyond_band_values_panics() {
+    LSHBloom::new(1_000_000, 0.01, 4);
+}
+
+#[test]
+fn atomic_index_matches_lshbloom() {
+    let mut lsh = LSHBloom::new(10_000, 0.01, 2);
+    let atomic = AtomicLSHBloom::new(10_000, 0.01, 2);
+    for i in 0..5_000u64 {
+        let hash = SimHash(i.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        lsh.insert(hash);
+        atomic.insert(hash);
+    }
+
+    assert_eq!(atomic.len(), 5_000);
+    assert_eq!(atomic.rows_per_band(), lsh.rows_per_band());
+    for i in 0..10_000u64 {
+        let hash = SimHash(i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ 0xFFFF);
+        assert_eq!(atomic.query_similar(hash), lsh.query_similar(hash));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Number of bands must divide 64")]
+fn invalid_band_count_panics() {
+    LSHBloom::new(100, 0.01, 3);
+}
+
+proptest! {
+    #[test]
+    fn inserted_signatures_always_match(hashes in prop::collection::vec(any::<u64>(), 1..200)) {
+        let mut lsh = LSHBloom::new(200, 0.01, 8);
+
+        for h in &hashes {
+            lsh.insert(SimHash(*h));
+        }
+
+        for h in &hashes {
+            prop_assert!(lsh.query_similar(SimHash(*h)));
+        }
+    }
+}
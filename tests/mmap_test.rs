@@ -0,0 +1,168 @@
+//! Tests for the memory-mapped Bloom filter.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter};
+
+use kaka::MembershipQuery;
+use kaka::bloom::BloomFilter;
+use kaka::mmap::MmapBloomFilter;
+use kaka::persist::PersistError;
+
+fn url(i: usize) -> String {
+    format!("https://example.com/page{}", i)
+}
+
+#[test]
+fn opens_file_written_by_bloom_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+
+    let mut bloom = BloomFilter::with_seed(1000, 0.01, 7);
+    for i in 0..1000 {
+        bloom.insert(&url(i));
+    }
+    bloom
+        .save(BufWriter::new(File::create(&path).unwrap()))
+        .unwrap();
+
+    let mapped = MmapBloomFilter::open(&path).unwrap();
+    assert!(!mapped.is_writable());
+    assert_eq!(mapped.num_bits(), bloom.num_bits());
+    assert_eq!(mapped.num_hashes(), bloom.num_hashes());
+    assert_eq!(mapped.items_inserted(), 1000);
+    mapped.verify().unwrap();
+    assert_eq!(mapped.fill_ratio(), bloom.fill_ratio());
+    assert_eq!(
+        mapped.estimated_cardinality(),
+        bloom.estimated_cardinality()
+    );
+
+    for i in 0..1000 {
+        assert!(mapped.contains(&url(i)));
+    }
+    for i in 1000..2000 {
+        assert_eq!(mapped.contains(&url(i)), bloom.contains(&url(i)));
+    }
+}
+
+#[test]
+fn created_filter_persists_through_flush() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+
+    let mut mapped = MmapBloomFilter::create(&path, 1000, 0.01, 7).unwrap();
+    for i in 0..500 {
+        mapped.insert(&url(i)).unwrap();
+    }
+    mapped.flush().unwrap();
+    drop(mapped);
+
+    let loaded = BloomFilter::load(BufReader::new(File::open(&path).unwrap())).unwrap();
+    assert_eq!(loaded.items_inserted(), 500);
+    assert_eq!(loaded.seed(), 7);
+    for i in 0..500 {
+        assert!(loaded.contains(&url(i)));
+    }
+
+    let mut reopened = MmapBloomFilter::open_mut(&path).unwrap();
+    reopened.insert(&url(500)).unwrap();
+    reopened.flush().unwrap();
+    assert!(MmapBloomFilter::open(&path).unwrap().contains(&url(500)));
+}
+
+#[test]
+fn readers_share_one_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+
+    let mut writer = MmapBloomFilter::create(&path, 100, 0.01, 1).unwrap();
+    writer.insert("https://example.com/").unwrap();
+    writer.flush().unwrap();
+
+    let a = MmapBloomFilter::open(&path).unwrap();
+    let b = MmapBloomFilter::open(&path).unwrap();
+    assert!(a.contains("https://example.com/"));
+    assert!(b.contains("https://example.com/"));
+}
+
+#[test]
+fn truncated_file_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+
+    MmapBloomFilter::create(&path, 10_000, 0.01, 1).unwrap();
+    let file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(64).unwrap();
+
+    assert!(matches!(
+        MmapBloomFilter::open(&path),
+        Err(PersistError::Corrupt(_))
+    ));
+}
+
+#[test]
+fn verify_detects_unflushed_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+
+    let mut mapped = MmapBloomFilter::create(&path, 100, 0.01, 1).unwrap();
+    mapped.verify().unwrap();
+    mapped.insert("https://example.com/").unwrap();
+    assert!(matches!(
+        mapped.verify(),
+        Err(PersistError::ChecksumMismatch)
+    ));
+    mapped.flush().unwrap();
+    mapped.verify().unwrap();
+}
+
+#[test]
+fn read_only_insert_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+    MmapBloomFilter::create(&path, 100, 0.01, 1).unwrap();
+
+    let mut mapped = MmapBloomFilter::open(&path).unwrap();
+    let err = mapped.insert("x").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(!mapped.contains("x"));
+    assert_eq!(mapped.items_inserted(), 0);
+}
+
+#[test]
+fn read_only_flush_keeps_stored_checksum() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+    MmapBloomFilter::create(&path, 100, 0.01, 1).unwrap();
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    std::fs::write(&path, &bytes).unwrap();
+
+    let mut mapped = MmapBloomFilter::open(&path).unwrap();
+    assert!(matches!(
+        mapped.verify(),
+        Err(PersistError::ChecksumMismatch)
+    ));
+    mapped.flush().unwrap();
+    assert!(
+        matches!(mapped.verify(), Err(PersistError::ChecksumMismatch)),
+        "flush hid the corruption"
+    );
+}
+
+#[test]
+fn implements_membership_query() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("seen.kblm");
+    let mut writer = MmapBloomFilter::create(&path, 1000, 0.01, 1).unwrap();
+    writer.insert("https://example.com/").unwrap();
+    writer.flush().unwrap();
+
+    let store: Box<dyn MembershipQuery> = Box::new(MmapBloomFilter::open(&path).unwrap());
+    assert!(store.contains("https://example.com/"));
+    assert_eq!(store.len_estimate(), 1);
+    assert_eq!(store.memory_bytes(), writer.num_bits().div_ceil(64) * 8);
+    assert!(store.fp_rate() < 0.01);
+}
@@ -1,4 +1,6 @@
-use kaka::UrlNormalizer;
+use kaka::{NormalizerConfig, PathPolicy, TrailingSlash, UrlNormalizer};
+use proptest::prelude::*;
+use url::Url;
 
 #[test]
 fn scheme_normalization() {
@@ -28,10 +30,125 @@ fn port_normalization() {
         "http://example.com/"
     );
 
-    // Non-default ports are removed by current config
+    // Non-default ports are kept
     assert_eq!(
         n.normalize("http://example.com:8080").unwrap(),
-        "http://example.com/"
+        "http://example.com:8080/"
+    );
+    assert_eq!(
+        n.normalize("https://example.com:80/a").unwrap(),
+        "https://example.com:80/a"
+    );
+}
+
+#[test]
+fn explicit_ports_when_default_port_kept() {
+    let n = UrlNormalizer::builder().remove_default_port(false).build();
+
+    assert_eq!(
+        n.normalize("http://example.com/").unwrap(),
+        "http://example.com:80/"
+    );
+    assert_eq!(
+        n.normalize("https://example.com:443/").unwrap(),
+        "https://example.com:443/"
+    );
+    assert_eq!(
+        n.normalize("http://example.com:8080/").unwrap(),
+        "http://example.com:8080/"
+    );
+}
+
+#[test]
+fn scheme_case_kept_when_disabled() {
+    let n = UrlNormalizer::builder().lowercase_scheme(false).build();
+
+    assert_eq!(
+        n.normalize("HTTP://example.com").unwrap(),
+        "HTTP://example.com/"
+    );
+    assert_eq!(
+        n.normalize("  HtTpS://example.com").unwrap(),
+        "HtTpS://example.com/"
+    );
+}
+
+#[test]
+fn hostname_case_for_non_special_schemes() {
+    let lower = UrlNormalizer::new();
+    let keep = UrlNormalizer::builder().lowercase_hostname(false).build();
+
+    assert_eq!(
+        lower.normalize("git://Example.COM/repo").unwrap(),
+        "git://example.com/repo"
+    );
+    assert_eq!(
+        keep.normalize("git://Example.COM/repo").unwrap(),
+        "git://Example.COM/repo"
+    );
+    // Special schemes are lowercased by parsing either way
+    assert_eq!(
+        keep.normalize("https://Example.COM/").unwrap(),
+        "https://example.com/"
+    );
+}
+
+#[test]
+fn www_kept_when_disabled() {
+    let n = UrlNormalizer::builder().remove_www(false).build();
+
+    assert_eq!(
+        n.normalize("https://www.example.com/").unwrap(),
+        "https://www.example.com/"
+    );
+}
+
+#[test]
+fn query_order_kept_when_disabled() {
+    let n = UrlNormalizer::builder().sort_query_params(false).build();
+
+    assert_eq!(
+        n.normalize("https://example.com/?b=2&a=1").unwrap(),
+        "https://example.com/?b=2&a=1"
+    );
+}
+
+#[test]
+fn builder_tracking_params() {
+    let n = UrlNormalizer::builder()
+        .remove_tracking_params(&["session"])
+        .build();
+    assert_eq!(
+        n.normalize("https://example.com/?session=1&utm_source=x&q=a")
+            .unwrap(),
+        "https://example.com/?q=a"
+    );
+
+    let n = UrlNormalizer::builder().keep_all_params().build();
+    assert_eq!(
+        n.normalize("https://example.com/?utm_source=x").unwrap(),
+        "https://example.com/?utm_source=x"
+    );
+}
+
+#[test]
+fn with_config_matches_builder() {
+    let config = NormalizerConfig {
+        remove_fragment: false,
+        sort_query_params: false,
+        ..NormalizerConfig::default()
+    };
+    let input = "https://example.com/?b=2&a=1#frag";
+
+    assert_eq!(
+        UrlNormalizer::with_config(config.clone())
+            .normalize(input)
+            .unwrap(),
+        UrlNormalizer::builder()
+            .config(config)
+            .build()
+            .normalize(input)
+            .unwrap()
     );
 }
 
@@ -78,6 +195,20 @@ fn fragment_removal() {
     );
 }
 
+#[test]
+fn fragment_kept_when_disabled() {
+    let n = UrlNormalizer::builder().remove_fragment(false).build();
+
+    assert_eq!(
+        n.normalize("https://example.com/page#section").unwrap(),
+        "https://example.com/page#section"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/page").unwrap(),
+        "https://example.com/page"
+    );
+}
+
 #[test]
 fn complex_url_normalization() {
     let n = UrlNormalizer::new();
@@ -127,3 +258,258 @@ fn malformed_urls_return_error() {
     let n = UrlNormalizer::new();
     assert!(n.normalize("not a url").is_err());
 }
+
+#[test]
+fn escaped_delimiters_stay_distinct() {
+    let n = UrlNormalizer::new();
+
+    let escaped = n.normalize("https://example.com/?q=a%26b").unwrap();
+    let split = n.normalize("https://example.com/?q=a&b").unwrap();
+    assert_eq!(escaped, "https://example.com/?q=a%26b");
+    assert_eq!(split, "https://example.com/?b&q=a");
+
+    assert_eq!(
+        n.normalize("https://example.com/?a=1%3D2&b=x%2By+z")
+            .unwrap(),
+        "https://example.com/?a=1%3D2&b=x%2By+z"
+    );
+}
+
+#[test]
+fn percent_encoding_normal_form() {
+    let n = UrlNormalizer::new();
+
+    // Unreserved characters are decoded, hex digits uppercased
+    assert_eq!(
+        n.normalize("https://example.com/%7euser/%41b%2d?k%5fey=%c3%a9")
+            .unwrap(),
+        "https://example.com/~user/Ab-?k_ey=%C3%A9"
+    );
+
+    // Reserved characters keep their escaped form
+    assert_eq!(
+        n.normalize("https://example.com/a%2fb/c%3Fd").unwrap(),
+        "https://example.com/a%2Fb/c%3Fd"
+    );
+
+    // Characters not allowed in a URL are escaped
+    assert_eq!(
+        n.normalize("https://example.com/a b/%zz?q=x y|z").unwrap(),
+        "https://example.com/a%20b/%25zz?q=x%20y%7Cz"
+    );
+}
+
+#[test]
+fn escaped_tracking_params_are_removed() {
+    let n = UrlNormalizer::new();
+
+    assert_eq!(
+        n.normalize("https://example.com/?utm%5Fsource=x&q=1")
+            .unwrap(),
+        "https://example.com/?q=1"
+    );
+}
+
+#[test]
+fn fragment_is_percent_normalized() {
+    let n = UrlNormalizer::builder().remove_fragment(false).build();
+
+    assert_eq!(
+        n.normalize("https://example.com/#a%7e%2fb").unwrap(),
+        "https://example.com/#a~%2Fb"
+    );
+}
+
+#[test]
+fn trailing_slash_policies() {
+    let cases = [
+        (TrailingSlash::Keep, ["/dir/", "/dir", "/a.html", "/"]),
+        (TrailingSlash::Strip, ["/dir", "/dir", "/a.html", "/"]),
+        (TrailingSlash::Add, ["/dir/", "/dir/", "/a.html", "/"]),
+    ];
+
+    for (policy, expected) in cases {
+        let n = UrlNormalizer::builder()
+            .path_policy(PathPolicy::default().with_trailing_slash(policy))
+            .build();
+        for (input, expected) in ["/dir/", "/dir", "/a.html", "/"].iter().zip(expected) {
+            assert_eq!(
+                n.normalize(&format!("https://example.com{}", input))
+                    .unwrap(),
+                format!("https://example.com{}", expected),
+                "{:?} on {}",
+                policy,
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn index_files_and_duplicate_slashes() {
+    let n = UrlNormalizer::builder()
+        .path_policy(
+            PathPolicy::default()
+                .with_default_index_files()
+                .with_collapsed_slashes(true)
+                .with_trailing_slash(TrailingSlash::Keep),
+        )
+        .build();
+
+    assert_eq!(
+        n.normalize("https://example.com/docs//index.html").unwrap(),
+        "https://example.com/docs/"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/default.aspx?x=1").unwrap(),
+        "https://example.com/?x=1"
+    );
+    // Only whole final segments are removed
+    assert_eq!(
+        n.normalize("https://example.com/myindex.html").unwrap(),
+        "https://example.com/myindex.html"
+    );
+    // Slashes are kept unless collapsing is enabled
+    assert_eq!(
+        UrlNormalizer::new()
+            .normalize("https://example.com/a//b")
+            .unwrap(),
+        "https://example.com/a//b"
+    );
+}
+
+#[test]
+fn per_domain_path_policies() {
+    let iis = PathPolicy::default()
+        .with_lowercase(true)
+        .with_default_index_files();
+    let n = UrlNormalizer::builder()
+        .path_policy(PathPolicy::default().with_trailing_slash(TrailingSlash::Keep))
+        .domain_path_policy("legacy.example.com", iis)
+        .build();
+
+    // Matching domain and its subdomains use the domain policy
+    assert_eq!(
+        n.normalize("https://legacy.example.com/Docs/Default.ASPX")
+            .unwrap(),
+        "https://legacy.example.com/docs"
+    );
+    assert_eq!(
+        n.normalize("https://www.shop.legacy.example.com/A/")
+            .unwrap(),
+        "https://shop.legacy.example.com/a"
+    );
+
+    // Other hosts use the global policy
+    assert_eq!(
+        n.normalize("https://example.com/Docs/").unwrap(),
+        "https://example.com/Docs/"
+    );
+    assert_eq!(
+        n.normalize("https://notlegacy.example.com/Docs/").unwrap(),
+        "https://notlegacy.example.com/Docs/"
+    );
+}
+
+#[test]
+fn path_policy_through_config() {
+    let mut config = NormalizerConfig::default();
+    config.domain_paths.insert(
+        "example.org".to_string(),
+        PathPolicy::default().with_trailing_slash(TrailingSlash::Add),
+    );
+    let n = UrlNormalizer::with_config(config);
+
+    assert_eq!(
+        n.normalize("https://example.org/dir").unwrap(),
+        "https://example.org/dir/"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/dir/").unwrap(),
+        "https://example.com/dir"
+    );
+}
+
+#[test]
+fn path_policies_see_normalized_escapes() {
+    let n = UrlNormalizer::builder()
+        .path_policy(
+            PathPolicy::default()
+                .with_lowercase(true)
+                .with_default_index_files(),
+        )
+        .build();
+
+    assert_eq!(
+        n.normalize("https://example.com/%41BC").unwrap(),
+        n.normalize("https://example.com/ABC").unwrap()
+    );
+    assert_eq!(
+        n.normalize("https://example.com/d/%69ndex.html").unwrap(),
+        "https://example.com/d"
+    );
+    // Lowercasing leaves escapes of reserved bytes in normal form
+    assert_eq!(
+        n.normalize("https://example.com/A%2FB%C3%89").unwrap(),
+        "https://example.com/a%2Fb%C3%89"
+    );
+    assert_eq!(
+        UrlNormalizer::new()
+            .normalize("https://example.com/cart;%6Asessionid=1A2B?x=1")
+            .unwrap(),
+        "https://example.com/cart?x=1"
+    );
+}
+
+#[test]
+fn host_less_urls_keep_their_form() {
+    let n = UrlNormalizer::new();
+
+    for input in [
+        "mailto:x@y.com",
+        "urn:isbn:0451450523",
+        "data:text/plain,a%2Cb",
+        "foo:/a/b?q=1",
+        "mailto:someone@example.com?subject=Hi",
+    ] {
+        let output = n.normalize(input).unwrap();
+        assert!(!output.contains("//"), "{} became {}", input, output);
+        assert_eq!(
+            Url::parse(&output).unwrap(),
+            Url::parse(input).unwrap(),
+            "{} became {}",
+            input,
+            output
+        );
+    }
+    assert_eq!(n.normalize("MAILTO:x@%79.com").unwrap(), "mailto:x@y.com");
+}
+
+#[test]
+fn empty_authority_is_kept() {
+    let n = UrlNormalizer::new();
+
+    assert_eq!(n.normalize("file:///tmp/a").unwrap(), "file:///tmp/a");
+    assert_eq!(n.normalize("FILE:///tmp/%7ea").unwrap(), "file:///tmp/~a");
+    assert_eq!(n.normalize("foo:///a/b").unwrap(), "foo:///a/b");
+    assert_eq!(
+        n.normalize("file://server/share").unwrap(),
+        "file://server/share"
+    );
+}
+
+proptest! {
+    #[test]
+    fn output_is_valid_and_stable(
+        path in "[a-zA-Z0-9%/._~!$&'()*+,;=:@ |\\^-]{0,30}",
+        query in "[a-zA-Z0-9%/._~!$&'()*+,;=:@?| ^-]{0,30}",
+    ) {
+        let n = UrlNormalizer::new();
+        let input = format!("https://example.com/{}?{}", path, query);
+
+        let once = n.normalize(&input).unwrap();
+        let parsed = Url::parse(&once).unwrap();
+        prop_assert_eq!(parsed.as_str(), once.as_str());
+        prop_assert_eq!(n.normalize(&once).unwrap(), once);
+    }
+}
@@ -0,0 +1,149 @@
+//! Tests for pattern-based query parameter rules.
+
+use kaka::params::TRACKING_PREFIXES;
+use kaka::{ParamMatcher, UrlNormalizer};
+use proptest::prelude::*;
+
+#[test]
+fn known_family_keys_are_removed_by_default() {
+    let n = UrlNormalizer::new();
+
+    assert_eq!(
+        n.normalize("https://example.com/?utm_id=1&pk_campaign=x&hsa_acc=2&_hsenc=y&mkt_tok=z&q=1")
+            .unwrap(),
+        "https://example.com/?q=1"
+    );
+    // Other keys sharing a family prefix are left alone
+    assert_eq!(
+        n.normalize("https://example.com/?pk_id=5&pk=3&_hsx=1")
+            .unwrap(),
+        "https://example.com/?_hsx=1&pk=3&pk_id=5"
+    );
+}
+
+#[test]
+fn families_are_opt_in() {
+    let n = TRACKING_PREFIXES
+        .iter()
+        .fold(UrlNormalizer::builder(), |builder, prefix| {
+            builder.remove_params(ParamMatcher::prefix(prefix))
+        })
+        .build();
+
+    assert_eq!(
+        n.normalize("https://example.com/?pk_id=5&pk=3&hsa_new=1&utm_x=2")
+            .unwrap(),
+        "https://example.com/?pk=3"
+    );
+}
+
+#[test]
+fn matcher_kinds() {
+    assert!(ParamMatcher::exact("ref").matches("ref"));
+    assert!(!ParamMatcher::exact("ref").matches("refs"));
+
+    assert!(ParamMatcher::prefix("pk_").matches("pk_kwd"));
+    assert!(!ParamMatcher::prefix("pk_").matches("pkg"));
+
+    let glob = ParamMatcher::glob("_hs*");
+    assert!(glob.matches("_hsmi") && glob.matches("_hs"));
+    assert!(!glob.matches("x_hsmi"));
+    let glob = ParamMatcher::glob("ad?_*id");
+    assert!(glob.matches("ads_campaignid") && glob.matches("adx_id"));
+    assert!(!glob.matches("ad_id") && !glob.matches("ads_idx"));
+
+    let regex = ParamMatcher::regex("^(?i)trk[0-9]+$").unwrap();
+    assert!(regex.matches("TRK42"));
+    assert!(!regex.matches("trk"));
+    assert!(ParamMatcher::regex("(").is_err());
+
+    let predicate = ParamMatcher::predicate(|key| key.len() > 10);
+    assert!(predicate.matches("very_long_key"));
+    assert!(!predicate.matches("short"));
+}
+
+#[test]
+fn builder_rules() {
+    let n = UrlNormalizer::builder()
+        .remove_params(ParamMatcher::glob("ad?_*"))
+        .remove_params(ParamMatcher::regex("^x-").unwrap())
+        .remove_params_matching(|key| key.starts_with('_'))
+        .build();
+
+    assert_eq!(
+        n.normalize("https://example.com/?ads_id=1&x-trace=2&_private=3&a=4")
+            .unwrap(),
+        "https://example.com/?a=4"
+    );
+}
+
+#[test]
+fn domain_scoped_removal() {
+    let n = UrlNormalizer::builder()
+        .remove_params_for_domain("shop.com", ParamMatcher::exact("color"))
+        .build();
+
+    assert_eq!(
+        n.normalize("https://eu.shop.com/item?color=red&id=1")
+            .unwrap(),
+        "https://eu.shop.com/item?id=1"
+    );
+    assert_eq!(
+        n.normalize("https://myshop.com/item?color=red&id=1")
+            .unwrap(),
+        "https://myshop.com/item?color=red&id=1"
+    );
+}
+
+#[test]
+fn keep_rules_override_global_removal() {
+    let n = UrlNormalizer::builder()
+        .keep_params_for_domain("docs.rs", ParamMatcher::exact("ref"))
+        .keep_params_for_domain("analytics.io", ParamMatcher::prefix("utm_"))
+        .build();
+
+    assert_eq!(
+        n.normalize("https://docs.rs/page?ref=main&utm_source=x")
+            .unwrap(),
+        "https://docs.rs/page?ref=main"
+    );
+    assert_eq!(
+        n.normalize("https://www.analytics.io/?utm_source=x")
+            .unwrap(),
+        "https://analytics.io/?utm_source=x"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/page?ref=main").unwrap(),
+        "https://example.com/page"
+    );
+}
+
+#[test]
+fn keep_all_params_drops_defaults() {
+    let n = UrlNormalizer::builder()
+        .keep_all_params()
+        .remove_params(ParamMatcher::exact("b"))
+        .build();
+
+    assert_eq!(
+        n.normalize("https://example.com/?pk_kwd=1&utm_source=2&b=3")
+            .unwrap(),
+        "https://example.com/?pk_kwd=1&utm_source=2"
+    );
+}
+
+proptest! {
+    #[test]
+    fn star_glob_matches_everything(key in ".*") {
+        prop_assert!(ParamMatcher::glob("*").matches(&key));
+        prop_assert!(ParamMatcher::glob(&key.replace(['*', '?'], "")).matches(&key.replace(['*', '?'], "")));
+    }
+
+    #[test]
+    fn prefix_glob_equals_prefix(prefix in "[a-z_]{0,4}", key in "[a-z_]{0,8}") {
+        prop_assert_eq!(
+            ParamMatcher::glob(&format!("{}*", prefix)).matches(&key),
+            ParamMatcher::prefix(&prefix).matches(&key)
+        );
+    }
+}
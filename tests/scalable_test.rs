@@ -0,0 +1,94 @@
+//! Tests for the scalable Bloom filter.
+
+use kaka::scalable::ScalableBloomFilter;
+use kaka::{Config, DeduplicationEngine, FilterKind};
+use proptest::prelude::*;
+
+#[test]
+fn grows_past_initial_capacity() {
+    let mut bloom = ScalableBloomFilter::new(1000, 0.01);
+
+    for i in 0..20_000 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+
+    assert!(bloom.num_stages() > 1);
+    assert!(bloom.capacity() >= 20_000);
+    assert_eq!(bloom.items_inserted(), 20_000);
+
+    for i in 0..20_000 {
+        assert!(bloom.contains(&format!("https://example.com/{}", i)));
+    }
+}
+
+#[test]
+fn false_positive_bound_holds_after_growth() {
+    let mut bloom = ScalableBloomFilter::new(1000, 0.01);
+
+    for i in 0..50_000 {
+        bloom.insert(&format!("https://example.com/{}", i));
+    }
+
+    let trials = 100_000;
+    let false_positives = (50_000..50_000 + trials)
+        .filter(|i| bloom.contains(&format!("https://example.com/{}", i)))
+        .count();
+
+    let measured_fp = false_positives as f64 / trials as f64;
+    assert!(measured_fp <= 0.011, "measured {}", measured_fp);
+    assert!(bloom.false_positive_rate() <= bloom.false_positive_bound());
+}
+
+#[test]
+fn single_stage_within_capacity() {
+    let mut bloom = ScalableBloomFilter::new(1000, 0.01);
+
+    for i in 0..1000 {
+        bloom.insert(&i.to_string());
+    }
+
+    assert_eq!(bloom.num_stages(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Tightening ratio must be in (0, 1)")]
+fn invalid_tightening_ratio_panics() {
+    ScalableBloomFilter::with_params(100, 0.01, 2, 1.0, 0);
+}
+
+#[test]
+fn engine_keeps_accepting_new_urls_past_capacity() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1_000)
+            .with_filter(FilterKind::Scalable)
+            .disable_simhash(),
+    )
+    .unwrap();
+
+    let accepted = (0..20_000)
+        .filter(|i| {
+            !engine
+                .check_and_insert(&format!("https://example.com/page{}", i))
+                .unwrap()
+        })
+        .count();
+
+    assert!(accepted > 19_800, "accepted {}", accepted);
+    assert!(engine.false_positive_rate() <= 0.01);
+}
+
+proptest! {
+    #[test]
+    fn no_false_negatives(urls in prop::collection::vec(".*", 1..500)) {
+        let mut bloom = ScalableBloomFilter::new(16, 0.01);
+
+        for url in &urls {
+            bloom.insert(url);
+        }
+
+        for url in &urls {
+            prop_assert!(bloom.contains(url));
+        }
+    }
+}
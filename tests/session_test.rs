@@ -0,0 +1,138 @@
+//! Tests for session-ID stripping.
+
+use kaka::{SessionIdPolicy, UrlNormalizer};
+use proptest::prelude::*;
+
+#[test]
+fn path_parameters_are_stripped() {
+    let n = UrlNormalizer::new();
+
+    assert_eq!(
+        n.normalize("https://shop.example.com/cart;jsessionid=1A2B3C4D5E6F?item=7")
+            .unwrap(),
+        "https://shop.example.com/cart?item=7"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/a;JSESSIONID=x/b;v=2")
+            .unwrap(),
+        "https://example.com/a/b;v=2"
+    );
+}
+
+#[test]
+fn query_session_keys_are_stripped() {
+    let n = UrlNormalizer::new();
+
+    for key in ["PHPSESSID", "sid", "SessionID", "ASPSESSIONIDQQGGQGPG"] {
+        assert_eq!(
+            n.normalize(&format!("https://example.com/p?{}=abc&q=1", key))
+                .unwrap(),
+            "https://example.com/p?q=1",
+            "{} kept",
+            key
+        );
+    }
+}
+
+#[test]
+fn cookieless_asp_net_segments_are_removed() {
+    let n = UrlNormalizer::new();
+
+    assert_eq!(
+        n.normalize("https://example.com/(S(lit3py55t21z5v55vlm25s55))/page.aspx")
+            .unwrap(),
+        "https://example.com/page.aspx"
+    );
+    assert_eq!(
+        n.normalize("https://example.com/app/(A(xyz)S(abc)F(def))/")
+            .unwrap(),
+        "https://example.com/app"
+    );
+}
+
+#[test]
+fn token_values_need_opt_in() {
+    let url = "https://example.com/p?page=2&token=a8Fz3kQ9xV2mL7pR4tW1";
+
+    assert_eq!(UrlNormalizer::new().normalize(url).unwrap(), url);
+
+    let n = UrlNormalizer::builder()
+        .session_id_policy(SessionIdPolicy::default().with_token_detection(true))
+        .build();
+    assert_eq!(n.normalize(url).unwrap(), "https://example.com/p?page=2");
+    assert_eq!(
+        n.normalize("https://example.com/p;s=9f86d081884c7d659a2feaa0c55ad015")
+            .unwrap(),
+        "https://example.com/p"
+    );
+
+    // Base64 symbols arrive percent-encoded
+    assert_eq!(
+        n.normalize("https://example.com/p?page=2&x=dGhp%2BcyBp%2FcyBhIHRlc3Q0MjQy%3D%3D")
+            .unwrap(),
+        "https://example.com/p?page=2"
+    );
+}
+
+#[test]
+fn allowlist_overrides_detection() {
+    let n = UrlNormalizer::builder()
+        .session_id_policy(
+            SessionIdPolicy::default()
+                .with_token_detection(true)
+                .with_allowlist(&["SID", "commit"]),
+        )
+        .build();
+
+    let url = "https://example.com/p?commit=9f86d081884c7d659a2feaa0c55ad015&sid=42";
+    assert_eq!(n.normalize(url).unwrap(), url);
+}
+
+#[test]
+fn disabled_policy_keeps_everything() {
+    let n = UrlNormalizer::builder()
+        .session_id_policy(SessionIdPolicy::disabled())
+        .build();
+
+    let url = "https://example.com/a;jsessionid=1?sid=2";
+    assert_eq!(n.normalize(url).unwrap(), url);
+}
+
+#[test]
+fn token_heuristic() {
+    for token in [
+        "9f86d081884c7d659a2feaa0c55ad015",
+        "a8Fz3kQ9xV2mL7pR4tW1",
+        "dGhpcyBpcyBhIHRlc3Q0MjQy==",
+        "lit3py55t21z5v55vlm25s55",
+        "dGhp%2BcyBp%2FcyBhIHRlc3Q0MjQy%3D%3D",
+        "dGhp%2bcyBp%2fcyBhIHRlc3Q0MjQy%3d",
+    ] {
+        assert!(SessionIdPolicy::looks_like_token(token), "{}", token);
+    }
+
+    for value in [
+        "SummerSale2024Collection",
+        "12345678901234567",
+        "short1a2b",
+        "all-lowercase-words-here",
+        "9f86d081884c7d659a2feaa0c55ad015!",
+        "9f86d081884c7d659a2feaa0c55ad015%21",
+        "9f86d081884c%3D7d659a2feaa0c55ad015",
+    ] {
+        assert!(!SessionIdPolicy::looks_like_token(value), "{}", value);
+    }
+}
+
+proptest! {
+    #[test]
+    fn urls_differing_only_by_session_collapse(session in "[A-F0-9]{8,32}") {
+        let n = UrlNormalizer::new();
+        let base = n.normalize("https://example.com/shop/item?id=5").unwrap();
+
+        let in_path = format!("https://example.com/shop/item;jsessionid={}?id=5", session);
+        let in_query = format!("https://example.com/shop/item?id=5&PHPSESSID={}", session);
+        prop_assert_eq!(n.normalize(&in_path).unwrap(), base.clone());
+        prop_assert_eq!(n.normalize(&in_query).unwrap(), base);
+    }
+}
@@ -0,0 +1,190 @@
+//! Tests for the host-sharded deduplication engine.
+
+use kaka::persist::PersistError;
+use kaka::{Config, ConfigError, FilterKind, FingerprintWidth, ShardedDeduplicationEngine};
+use rayon::prelude::*;
+
+fn engine(shards: usize) -> ShardedDeduplicationEngine {
+    ShardedDeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100_000)
+            .with_false_positive_rate(0.001),
+        shards,
+    )
+    .unwrap()
+}
+
+#[test]
+fn check_and_insert_across_shards() {
+    let engine = engine(8);
+
+    assert!(!engine.check_and_insert("https://a.example.com/x").unwrap());
+    assert!(!engine.check_and_insert("https://b.example.com/x").unwrap());
+    assert!(engine.check_and_insert("https://a.example.com/x").unwrap());
+    assert!(engine.is_duplicate("https://b.example.com/x").unwrap());
+    assert!(engine.check_and_insert("not a url").is_err());
+
+    let stats = engine.stats();
+    assert_eq!(stats.total_checked, 4);
+    assert_eq!(stats.urls_inserted, 2);
+    assert_eq!(stats.duplicates_found, 1);
+}
+
+#[test]
+fn urls_of_one_host_share_a_shard() {
+    let engine = engine(16);
+
+    let shard = engine.shard_for("https://example.com/").unwrap();
+    for i in 0..100 {
+        let url = format!("https://www.example.com/page/{}?utm_source=x", i);
+        assert_eq!(engine.shard_for(&url).unwrap(), shard);
+    }
+
+    // Ports are not part of the routing key
+    assert_eq!(
+        engine.shard_for("https://example.com:8443/a?b#c").unwrap(),
+        shard
+    );
+    assert_eq!(
+        engine.shard_for("http://[::1]:8080/").unwrap(),
+        engine.shard_for("http://[::1]/").unwrap()
+    );
+
+    // Routing is stable across engines with the same seed
+    assert_eq!(
+        ShardedDeduplicationEngine::new(Config::default(), 16)
+            .unwrap()
+            .shard_for("https://example.com/other")
+            .unwrap(),
+        shard
+    );
+}
+
+#[test]
+fn hosts_spread_over_shards() {
+    let engine = engine(8);
+
+    let mut used = [false; 8];
+    for i in 0..200 {
+        used[engine
+            .shard_for(&format!("https://host{}.com/", i))
+            .unwrap()] = true;
+    }
+    assert!(used.iter().all(|&u| u));
+}
+
+#[test]
+fn per_shard_stats_and_capacity() {
+    let engine =
+        ShardedDeduplicationEngine::with_shard_capacities(Config::default(), &[1_000, 50_000])
+            .unwrap();
+    assert_eq!(engine.num_shards(), 2);
+    assert_eq!(engine.shard_capacity(1), 50_000);
+
+    let url = "https://example.com/a";
+    let shard = engine.shard_for(url).unwrap();
+    engine.check_and_insert(url).unwrap();
+    engine.check_and_insert(url).unwrap();
+
+    assert_eq!(engine.shard_stats(shard).total_checked, 2);
+    assert_eq!(engine.shard_stats(shard).duplicates_found, 1);
+    assert_eq!(engine.shard_stats(1 - shard).total_checked, 0);
+}
+
+#[test]
+fn clear_shard_evicts_only_that_shard() {
+    let engine = engine(64);
+
+    let a = "https://alpha.example/";
+    let b = (0..)
+        .map(|i| format!("https://beta{}.example/", i))
+        .find(|u| engine.shard_for(u).unwrap() != engine.shard_for(a).unwrap())
+        .unwrap();
+    engine.check_and_insert(a).unwrap();
+    engine.check_and_insert(&b).unwrap();
+
+    engine.clear_shard(engine.shard_for(a).unwrap());
+
+    assert!(!engine.is_duplicate(a).unwrap());
+    assert!(engine.is_duplicate(&b).unwrap());
+    assert_eq!(engine.stats().urls_inserted, 1);
+}
+
+#[test]
+fn shard_persistence_roundtrip() {
+    let source = engine(4);
+    let url = "https://example.com/persisted";
+    let shard = source.shard_for(url).unwrap();
+    source.check_and_insert(url).unwrap();
+
+    let mut buf = Vec::new();
+    source.save_shard(shard, &mut buf).unwrap();
+
+    let target = engine(4);
+    assert!(!target.is_duplicate(url).unwrap());
+    target.load_shard(shard, buf.as_slice()).unwrap();
+    assert!(target.is_duplicate(url).unwrap());
+
+    let other_seed = ShardedDeduplicationEngine::new(Config::default().with_seed(1), 4).unwrap();
+    assert!(other_seed.load_shard(shard, buf.as_slice()).is_err());
+
+    // Same seed, but shards sized for a different layout
+    let resized =
+        ShardedDeduplicationEngine::with_shard_capacities(Config::default(), &[10; 4]).unwrap();
+    assert!(matches!(
+        resized.load_shard(shard, buf.as_slice()),
+        Err(PersistError::Corrupt(_))
+    ));
+    let other_rate = ShardedDeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100_000)
+            .with_false_positive_rate(0.01),
+        4,
+    )
+    .unwrap();
+    assert!(other_rate.load_shard(shard, buf.as_slice()).is_err());
+}
+
+#[test]
+fn parallel_inserts_are_exact_per_url() {
+    let engine = engine(8);
+
+    let new_count = (0..20_000)
+        .into_par_iter()
+        .filter(|i| {
+            !engine
+                .check_and_insert(&format!("https://host{}.com/{}", i % 50, i % 5_000))
+                .unwrap()
+        })
+        .count();
+
+    assert_eq!(new_count as u64, engine.stats().urls_inserted);
+    assert!(new_count <= 5_000);
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    assert!(matches!(
+        ShardedDeduplicationEngine::new(Config::default().with_filter(FilterKind::Scalable), 4),
+        Err(ConfigError::UnsupportedFilter(FilterKind::Scalable))
+    ));
+    assert!(matches!(
+        ShardedDeduplicationEngine::with_shard_capacities(Config::default(), &[10, 0]),
+        Err(ConfigError::ZeroCapacity)
+    ));
+    assert!(matches!(
+        ShardedDeduplicationEngine::new(
+            Config::default().with_exact_verification(FingerprintWidth::Bits64),
+            4
+        ),
+        Err(ConfigError::UnsupportedVerification)
+    ));
+    assert!(matches!(
+        ShardedDeduplicationEngine::new(Config::default(), 0),
+        Err(ConfigError::ZeroShards)
+    ));
+    assert!(matches!(
+        ShardedDeduplicationEngine::with_shard_capacities(Config::default(), &[]),
+        Err(ConfigError::ZeroShards)
+    ));
+}
@@ -1,4 +1,4 @@
-use kaka::simhash::{SimHash, SimHashEngine};
+use kaka::simhash::SimHashEngine;
 
 #[test]
 fn hash_consistency() {
@@ -0,0 +1,207 @@
+//! Tests for exact verification of filter positives.
+
+use std::collections::BTreeSet;
+
+use kaka::verify::fingerprint;
+use kaka::{
+    Config, ConfigError, DeduplicationEngine, EngineError, FilterKind, FingerprintStore,
+    FingerprintWidth, MemoryFingerprintStore,
+};
+use proptest::prelude::*;
+
+/// Engine whose filter is far too small, so positives are mostly false.
+fn overloaded(verification: Option<FingerprintWidth>) -> DeduplicationEngine {
+    let mut config = Config::default()
+        .with_capacity(10)
+        .with_false_positive_rate(0.1)
+        .disable_simhash();
+    if let Some(width) = verification {
+        config = config.with_exact_verification(width);
+    }
+    DeduplicationEngine::new(config).unwrap()
+}
+
+#[test]
+fn verification_recovers_false_positives() {
+    for width in [FingerprintWidth::Bits64, FingerprintWidth::Bits128] {
+        let mut engine = overloaded(Some(width));
+        for i in 0..1000 {
+            let url = format!("https://example.com/{}", i);
+            assert!(!engine.check_and_insert(&url).unwrap(), "{} lost", url);
+        }
+
+        let stats = engine.stats();
+        assert_eq!(stats.urls_inserted, 1000);
+        assert_eq!(stats.duplicates_found, 0);
+        assert!(stats.false_positives_rejected > 0);
+    }
+}
+
+#[test]
+fn without_verification_false_positives_are_dropped() {
+    let mut engine = overloaded(None);
+    for i in 0..1000 {
+        engine
+            .check_and_insert(&format!("https://example.com/{}", i))
+            .unwrap();
+    }
+
+    let stats = engine.stats();
+    assert!(stats.duplicates_found > 0);
+    assert_eq!(stats.false_positives_rejected, 0);
+}
+
+#[test]
+fn verified_duplicates_are_reported() {
+    let mut engine = overloaded(Some(FingerprintWidth::Bits64));
+    for i in 0..100 {
+        engine
+            .check_and_insert(&format!("https://example.com/{}", i))
+            .unwrap();
+    }
+    for i in 0..100 {
+        let url = format!("https://example.com/{}", i);
+        assert!(engine.is_duplicate(&url).unwrap());
+        assert!(engine.check_and_insert(&url).unwrap());
+    }
+    assert!(!engine.is_duplicate("https://example.com/new").unwrap());
+
+    let stats = engine.stats();
+    assert_eq!(stats.duplicates_found, 100);
+    assert!(engine.fingerprint_memory_bytes() >= 100 * 8);
+}
+
+#[test]
+fn forget_removes_fingerprint() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100)
+            .with_filter(FilterKind::Counting)
+            .with_exact_verification(FingerprintWidth::Bits128)
+            .disable_simhash(),
+    )
+    .unwrap();
+    let url = "https://example.com/page";
+
+    engine.check_and_insert(url).unwrap();
+    assert!(!engine.forget("https://example.com/other").unwrap());
+    assert!(engine.forget(url).unwrap());
+    assert!(!engine.is_duplicate(url).unwrap());
+    assert!(!engine.check_and_insert(url).unwrap());
+}
+
+#[test]
+fn forget_without_deletion_support_always_errors() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(100)
+            .with_exact_verification(FingerprintWidth::Bits64)
+            .disable_simhash(),
+    )
+    .unwrap();
+    let url = "https://example.com/page";
+    engine.check_and_insert(url).unwrap();
+
+    // Known and unknown URLs fail alike
+    assert_eq!(engine.forget(url), Err(EngineError::DeletionUnsupported));
+    assert_eq!(
+        engine.forget("https://example.com/other"),
+        Err(EngineError::DeletionUnsupported)
+    );
+}
+
+#[test]
+fn windowed_filters_reject_verification() {
+    // Fingerprints never expire, so they would keep confirming expired
+    // URLs as duplicates
+    let config = Config::default()
+        .with_insert_window(100, 2)
+        .with_exact_verification(FingerprintWidth::Bits64);
+
+    assert_eq!(config.validate(), Err(ConfigError::UnsupportedVerification));
+    assert!(matches!(
+        DeduplicationEngine::new(config),
+        Err(ConfigError::UnsupportedVerification)
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Windowed filters do not support exact verification")]
+fn windowed_engine_rejects_custom_store() {
+    let config = Config::default()
+        .with_time_window(std::time::Duration::from_secs(60), 2)
+        .disable_simhash();
+    let _ = DeduplicationEngine::new(config)
+        .unwrap()
+        .with_fingerprint_store(OrderedStore::default());
+}
+
+/// Sorted fingerprint store, standing in for an on-disk index.
+#[derive(Default)]
+struct OrderedStore(BTreeSet<u128>);
+
+impl FingerprintStore for OrderedStore {
+    fn insert(&mut self, fingerprint: u128) -> bool {
+        self.0.insert(fingerprint)
+    }
+
+    fn contains(&self, fingerprint: u128) -> bool {
+        self.0.contains(&fingerprint)
+    }
+
+    fn remove(&mut self, fingerprint: u128) -> bool {
+        self.0.remove(&fingerprint)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.0.len() * 16
+    }
+}
+
+#[test]
+fn custom_store() {
+    let mut engine = DeduplicationEngine::new(Config::default().disable_simhash())
+        .unwrap()
+        .with_fingerprint_store(OrderedStore::default());
+
+    assert!(!engine.check_and_insert("https://example.com/").unwrap());
+    assert!(engine.check_and_insert("https://example.com/").unwrap());
+    assert_eq!(engine.fingerprint_memory_bytes(), 16);
+}
+
+#[test]
+fn narrow_store_keeps_low_half() {
+    let mut store = MemoryFingerprintStore::new(FingerprintWidth::Bits64);
+    assert_eq!(store.width(), FingerprintWidth::Bits64);
+
+    assert!(store.insert(1 << 64 | 7));
+    assert!(store.contains(7));
+    assert!(!store.insert(7));
+    assert_eq!(store.len(), 1);
+}
+
+proptest! {
+    #[test]
+    fn store_matches_hash_set(
+        items in prop::collection::vec(any::<String>(), 0..200),
+        seed in any::<u64>(),
+    ) {
+        let mut store = MemoryFingerprintStore::new(FingerprintWidth::Bits128);
+        let mut expected = std::collections::HashSet::new();
+
+        for item in &items {
+            prop_assert_eq!(
+                store.insert(fingerprint(item, seed)),
+                expected.insert(item.clone())
+            );
+        }
+        prop_assert_eq!(store.len(), expected.len());
+        for item in &items {
+            prop_assert!(store.contains(fingerprint(item, seed)));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Tests for sliding-window deduplication.
+
+use std::time::{Duration, Instant};
+
+use kaka::windowed::{Rotation, WindowedBloomFilter};
+use kaka::{Config, ConfigError, DeduplicationEngine, FilterKind};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[test]
+fn time_window_forgets_after_all_generations() {
+    let mut filter = WindowedBloomFilter::new(1000, 0.01, 3, Rotation::Interval(HOUR));
+    let start = Instant::now();
+
+    assert!(!filter.check_and_insert_at("a", start));
+    assert!(filter.check_and_insert_at("a", start + HOUR / 2));
+
+    // Still remembered two generations later
+    assert!(filter.contains_at("a", start + HOUR * 2 + HOUR / 2));
+
+    // Forgotten once its generation leaves the window
+    filter.tick(start + HOUR * 3 + HOUR / 2);
+    assert!(!filter.contains_at("a", start + HOUR * 3 + HOUR / 2));
+    assert!(!filter.check_and_insert_at("a", start + HOUR * 3 + HOUR / 2));
+    assert!(filter.contains_at("a", start + HOUR * 4));
+}
+
+#[test]
+fn expired_generations_are_ignored_without_tick() {
+    let mut filter = WindowedBloomFilter::new(1000, 0.01, 2, Rotation::Interval(HOUR));
+    let start = Instant::now();
+
+    filter.insert_at("a", start);
+    assert!(filter.contains_at("a", start + HOUR));
+    assert!(!filter.contains_at("a", start + HOUR * 3));
+}
+
+#[test]
+fn repeat_sightings_do_not_refresh() {
+    let mut filter = WindowedBloomFilter::new(1000, 0.01, 2, Rotation::Interval(HOUR));
+    let start = Instant::now();
+
+    filter.check_and_insert_at("a", start);
+    assert!(filter.check_and_insert_at("a", start + HOUR + HOUR / 2));
+    assert!(!filter.check_and_insert_at("a", start + HOUR * 2 + HOUR / 2));
+}
+
+#[test]
+fn long_idle_clears_everything() {
+    let mut filter = WindowedBloomFilter::new(1000, 0.01, 4, Rotation::Interval(HOUR));
+    let start = Instant::now();
+
+    filter.insert_at("a", start);
+    filter.tick(start + HOUR * 100);
+    assert_eq!(filter.items_inserted(), 0);
+    assert!(!filter.contains_at("a", start + HOUR * 100));
+}
+
+#[test]
+fn insert_count_rotation() {
+    let mut filter = WindowedBloomFilter::new(300, 0.01, 3, Rotation::Inserts(100));
+
+    filter.insert("first");
+    for i in 0..299 {
+        filter.insert(&format!("item-{}", i));
+    }
+    assert!(filter.contains("first"));
+
+    // One more generation pushes the first one out
+    for i in 299..399 {
+        filter.insert(&format!("item-{}", i));
+    }
+    assert!(!filter.contains("first"));
+    assert!(filter.contains("item-398"));
+    assert_eq!(filter.items_inserted(), 300);
+}
+
+#[test]
+fn false_positive_rate_within_bound() {
+    let mut filter = WindowedBloomFilter::new(30_000, 0.01, 3, Rotation::Inserts(10_000));
+    for i in 0..30_000 {
+        filter.insert(&format!("https://example.com/{}", i));
+    }
+
+    let false_positives = (30_000..130_000)
+        .filter(|i| filter.contains(&format!("https://example.com/{}", i)))
+        .count();
+    let rate = false_positives as f64 / 100_000.0;
+    assert!(rate < 0.015, "FP rate {} too high", rate);
+    assert!(filter.false_positive_rate() < 0.015);
+}
+
+#[test]
+fn engine_insert_window() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(200)
+            .with_insert_window(200, 2)
+            .disable_simhash(),
+    )
+    .unwrap();
+    let url = "https://example.com/recrawl";
+
+    assert!(!engine.check_and_insert(url).unwrap());
+    assert!(engine.check_and_insert(url).unwrap());
+    // Headroom for false positives, which are not inserted
+    for i in 0..250 {
+        engine
+            .check_and_insert(&format!("https://example.com/{}", i))
+            .unwrap();
+    }
+    assert!(!engine.check_and_insert(url).unwrap());
+}
+
+#[test]
+fn insert_window_larger_than_capacity() {
+    let mut engine = DeduplicationEngine::new(
+        Config::default()
+            .with_capacity(1000)
+            .with_insert_window(100_000, 4)
+            .disable_simhash(),
+    )
+    .unwrap();
+
+    let duplicates = (0..50_000)
+        .filter(|i| {
+            engine
+                .check_and_insert(&format!("https://example.com/{}", i))
+                .unwrap()
+        })
+        .count();
+    assert!(duplicates < 500, "{} new URLs dropped", duplicates);
+
+    let filter = WindowedBloomFilter::new(1000, 0.01, 4, Rotation::Inserts(25_000));
+    assert_eq!(filter.generation_capacity(), 25_000);
+}
+
+#[test]
+fn engine_time_window_config() {
+    let config = Config::default().with_time_window(HOUR * 24 * 7, 7);
+    assert_eq!(
+        config.filter,
+        FilterKind::Windowed {
+            generations: 7,
+            rotation: Rotation::Interval(HOUR * 24),
+        }
+    );
+
+    let mut engine = DeduplicationEngine::new(config).unwrap();
+    assert!(!engine.check_and_insert("https://example.com/").unwrap());
+    assert!(engine.check_and_insert("https://example.com/").unwrap());
+}
+
+#[test]
+fn invalid_windows_are_rejected() {
+    for config in [
+        Config::default().with_time_window(HOUR, 0),
+        Config::default().with_time_window(Duration::ZERO, 3),
+        Config::default().with_insert_window(0, 3),
+    ] {
+        assert_eq!(config.validate(), Err(ConfigError::InvalidWindow));
+    }
+}